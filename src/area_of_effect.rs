@@ -0,0 +1,99 @@
+use rltk::DistanceAlg;
+use rltk::LineAlg;
+use rltk::Point;
+
+use crate::components::AreaOfEffect;
+use crate::components::AreaShape;
+use crate::map::Map;
+use crate::map::TileType;
+
+/// Metade da abertura de um cone, em graus.
+const CONE_HALF_ANGLE: f32 = 30.0;
+
+/// Células atingidas por um efeito de área lançado de `origin` em direção a
+/// `target`. Explosões e quadrados se centram no alvo; cones e raios partem
+/// de quem lança e nunca incluem a própria origem.
+pub fn affected_tiles(map: &Map, origin: Point, target: Point, aoe: &AreaOfEffect) -> Vec<Point> {
+    let radius = aoe.radius as i32;
+    let mut tiles = match aoe.shape {
+        AreaShape::Burst => rltk::field_of_view(target, radius, map),
+        AreaShape::Square => {
+            let mut tiles = Vec::new();
+            for y in target.y - radius..=target.y + radius {
+                for x in target.x - radius..=target.x + radius {
+                    tiles.push(Point::new(x, y));
+                }
+            }
+            tiles
+        }
+        AreaShape::Cone => rltk::field_of_view(origin, radius, map)
+            .into_iter()
+            .filter(|p| in_cone(origin, target, *p))
+            .collect(),
+        AreaShape::Beam => beam_path(origin, target, radius)
+            .into_iter()
+            .take_while(|p| is_inside(map, *p) && !is_wall(map, *p))
+            .collect(),
+    };
+    tiles.retain(|p| is_inside(map, *p) && !is_wall(map, *p));
+    tiles
+}
+
+fn is_inside(map: &Map, p: Point) -> bool {
+    p.x > 0 && p.x < map.width as i32 - 1 && p.y > 0 && p.y < map.height as i32 - 1
+}
+
+fn is_wall(map: &Map, p: Point) -> bool {
+    map.tiles[map.xy_idx(p.x as usize, p.y as usize)] == TileType::Wall
+}
+
+/// Se `point` cai dentro do cone que sai de `origin` apontando para `target`.
+fn in_cone(origin: Point, target: Point, point: Point) -> bool {
+    if point == origin || target == origin {
+        return false;
+    }
+    let (ax, ay) = ((target.x - origin.x) as f32, (target.y - origin.y) as f32);
+    let (bx, by) = ((point.x - origin.x) as f32, (point.y - origin.y) as f32);
+    let cos = (ax * bx + ay * by) / ((ax * ax + ay * ay).sqrt() * (bx * bx + by * by).sqrt());
+    cos >= CONE_HALF_ANGLE.to_radians().cos()
+}
+
+/// Linha reta de `origin` na direção de `target`, continuando depois dele até
+/// completar `length` células.
+fn beam_path(origin: Point, target: Point, length: i32) -> Vec<Point> {
+    if target == origin {
+        return Vec::new();
+    }
+    let distance = DistanceAlg::Pythagoras.distance2d(origin, target);
+    let scale = length as f32 / distance;
+    let end = Point::new(
+        origin.x + ((target.x - origin.x) as f32 * scale).round() as i32,
+        origin.y + ((target.y - origin.y) as f32 * scale).round() as i32,
+    );
+    rltk::line2d(LineAlg::Bresenham, origin, end)
+        .into_iter()
+        .filter(|p| *p != origin)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cone_opens_towards_target() {
+        let origin = Point::new(10, 10);
+        let target = Point::new(15, 10);
+        assert!(in_cone(origin, target, Point::new(13, 11)));
+        assert!(!in_cone(origin, target, Point::new(11, 13)));
+        assert!(!in_cone(origin, target, Point::new(7, 10)));
+        assert!(!in_cone(origin, target, origin));
+    }
+
+    #[test]
+    fn beam_goes_past_the_target() {
+        let path = beam_path(Point::new(0, 0), Point::new(2, 0), 5);
+        assert_eq!(path.len(), 5);
+        assert_eq!(path.last(), Some(&Point::new(5, 0)));
+    }
+}
@@ -0,0 +1,155 @@
+use crate::components::Attributes;
+use crate::components::CombatStats;
+use crate::components::Skills;
+
+pub const XP_PER_LEVEL: i32 = 300;
+const XP_PER_HP: i32 = 10;
+
+/// Modificador de um atributo: 10 é a média humana e cada 2 pontos acima
+/// (ou abaixo) valem +1 (ou -1).
+pub fn attribute_bonus(value: i32) -> i32 {
+    (value - 10).div_euclid(2)
+}
+
+pub fn max_hp(attributes: &Attributes, level: i32) -> i32 {
+    let per_level = 6 + attribute_bonus(attributes.fitness);
+    attributes.fitness * 2 + (level - 1) * i32::max(1, per_level)
+}
+
+pub fn hit_bonus(attributes: &Attributes, skills: &Skills) -> i32 {
+    attribute_bonus(attributes.might) + skills.melee
+}
+
+/// Pontaria para arremessos e disparos depende da rapidez, não da força.
+pub fn ranged_hit_bonus(attributes: &Attributes) -> i32 {
+    attribute_bonus(attributes.quickness)
+}
+
+pub fn damage_bonus(attributes: &Attributes) -> i32 {
+    attribute_bonus(attributes.might)
+}
+
+pub fn armour_class(attributes: &Attributes, skills: &Skills) -> i32 {
+    10 + attribute_bonus(attributes.quickness) + skills.defense
+}
+
+/// Turnos em paz para recuperar um ponto de vida.
+pub const BASE_REGEN_INTERVAL: i32 = 10;
+
+/// Gente em forma se recupera mais rápido, mas nunca em menos de 3 turnos.
+pub fn regen_interval(attributes: &Attributes) -> i32 {
+    i32::max(3, BASE_REGEN_INTERVAL - attribute_bonus(attributes.fitness))
+}
+
+pub fn max_mana(attributes: &Attributes, skills: &Skills, level: i32) -> i32 {
+    attributes.intelligence + skills.magic * 2 + (level - 1) * 2
+}
+
+/// Quanto peso (em kg) dá para carregar sem ficar sobrecarregado.
+pub fn carry_capacity(attributes: &Attributes) -> f32 {
+    5.0 + attributes.might as f32 * 0.5
+}
+
+/// Acima da capacidade a criatura fica pesada; acima de uma vez e meia, lenta.
+/// Passar do dobro não é possível.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Encumbrance {
+    Unburdened,
+    Burdened,
+    Overloaded,
+}
+
+pub fn encumbrance(load: f32, capacity: f32) -> Encumbrance {
+    if load <= capacity {
+        Encumbrance::Unburdened
+    } else if load <= capacity * 1.5 {
+        Encumbrance::Burdened
+    } else {
+        Encumbrance::Overloaded
+    }
+}
+
+/// O limite absoluto de carga: nada além do dobro da capacidade.
+pub fn can_carry(load: f32, extra: f32, capacity: f32) -> bool {
+    load + extra <= capacity * 2.0
+}
+
+pub fn encumbrance_hit_penalty(encumbrance: Encumbrance) -> i32 {
+    match encumbrance {
+        Encumbrance::Unburdened => 0,
+        Encumbrance::Burdened => 2,
+        Encumbrance::Overloaded => 4,
+    }
+}
+
+/// Experiência ganha por derrotar uma criatura.
+pub fn xp_reward(victim: &CombatStats) -> i32 {
+    victim.max_hp as i32 * XP_PER_HP
+}
+
+/// Aumenta o atributo sorteado (de 1 a 4) e devolve o ID do seu nome.
+pub fn raise_attribute(attributes: &mut Attributes, roll: i32) -> &'static str {
+    match roll {
+        1 => {
+            attributes.might += 1;
+            "attribute.might"
+        }
+        2 => {
+            attributes.fitness += 1;
+            "attribute.fitness"
+        }
+        3 => {
+            attributes.quickness += 1;
+            "attribute.quickness"
+        }
+        _ => {
+            attributes.intelligence += 1;
+            "attribute.intelligence"
+        }
+    }
+}
+
+/// Estatísticas de combate com a vida cheia para o nível dado.
+pub fn combat_stats(attributes: &Attributes, skills: &Skills, level: i32) -> CombatStats {
+    let max_hp = max_hp(attributes, level);
+    CombatStats {
+        max_hp: max_hp as u32,
+        hp: max_hp,
+        armour_class: armour_class(attributes, skills),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bonus_rounds_towards_negative() {
+        assert_eq!(attribute_bonus(10), 0);
+        assert_eq!(attribute_bonus(11), 0);
+        assert_eq!(attribute_bonus(12), 1);
+        assert_eq!(attribute_bonus(9), -1);
+        assert_eq!(attribute_bonus(4), -3);
+    }
+
+    #[test]
+    fn encumbrance_thresholds() {
+        assert_eq!(encumbrance(20.0, 20.0), Encumbrance::Unburdened);
+        assert_eq!(encumbrance(25.0, 20.0), Encumbrance::Burdened);
+        assert_eq!(encumbrance(31.0, 20.0), Encumbrance::Overloaded);
+        assert!(can_carry(39.0, 1.0, 20.0));
+        assert!(!can_carry(39.5, 1.0, 20.0));
+    }
+
+    #[quickcheck]
+    fn max_hp_grows_with_level(fitness: u8, level: u8) -> bool {
+        let attributes = Attributes {
+            might: 10,
+            fitness: fitness as i32 % 20,
+            quickness: 10,
+            intelligence: 10,
+        };
+        let level = 1 + level as i32 % 20;
+        max_hp(&attributes, level + 1) > max_hp(&attributes, level)
+    }
+}
@@ -0,0 +1,76 @@
+use rltk::Point;
+use specs::World;
+
+use crate::layout::Layout;
+use crate::layout::Region;
+
+/// Janela sobre o mapa, centrada no jogador. Converte coordenadas do mundo
+/// para a tela e de volta.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Camera {
+    /// Célula do mundo que aparece no canto superior esquerdo de `view`.
+    pub origin: Point,
+    /// Pedaço do console onde o mapa é desenhado.
+    pub view: Region,
+}
+
+impl Camera {
+    pub fn centered_on(center: Point, view: Region) -> Self {
+        Self {
+            origin: Point::new(center.x - view.width / 2, center.y - view.height / 2),
+            view,
+        }
+    }
+
+    pub fn from_ecs(ecs: &World) -> Self {
+        Self::centered_on(*ecs.fetch::<Point>(), Layout::default().map)
+    }
+
+    /// Onde a célula do mundo aparece na tela, se aparecer.
+    pub fn to_screen(self, world: Point) -> Option<Point> {
+        let screen = world - self.origin + Point::new(self.view.x, self.view.y);
+        if self.view.contains(screen.x, screen.y) {
+            Some(screen)
+        } else {
+            None
+        }
+    }
+
+    /// Que célula do mundo está embaixo da posição da tela, se for no mapa.
+    pub fn to_world(self, screen: (i32, i32)) -> Option<Point> {
+        if self.view.contains(screen.0, screen.1) {
+            Some(self.origin + Point::new(screen.0 - self.view.x, screen.1 - self.view.y))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[quickcheck]
+    fn screen_and_world_round_trip(cx: i16, cy: i16, sx: u8, sy: u8) -> bool {
+        let view = Layout::default().map;
+        let camera = Camera::centered_on(Point::new(cx as i32, cy as i32), view);
+        let screen = (
+            view.x + sx as i32 % view.width,
+            view.y + sy as i32 % view.height,
+        );
+        let world = camera.to_world(screen).unwrap();
+        camera.to_screen(world) == Some(Point::new(screen.0, screen.1))
+    }
+
+    #[test]
+    fn player_is_in_the_middle() {
+        let view = Layout::default().map;
+        let player = Point::new(3, 100);
+        let camera = Camera::centered_on(player, view);
+        assert_eq!(
+            camera.to_screen(player),
+            Some(Point::new(view.width / 2, view.height / 2))
+        );
+        assert_eq!(camera.to_world((-1, 0)), None);
+    }
+}
@@ -0,0 +1,118 @@
+use rltk::Point;
+use specs::prelude::*;
+
+use crate::components::*;
+use crate::faction::reaction;
+use crate::faction::Reaction;
+use crate::map::Map;
+use crate::monster_ai_system::step_towards;
+use crate::RunState;
+
+/// Distância do jogador a partir da qual um companheiro volta a segui-lo.
+const FOLLOW_DISTANCE: f32 = 2.5;
+
+pub struct CompanionAISystem {}
+
+impl<'a> System<'a> for CompanionAISystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteExpect<'a, Map>,
+        ReadExpect<'a, Point>,
+        ReadExpect<'a, RunState>,
+        ReadStorage<'a, Companion>,
+        ReadStorage<'a, Faction>,
+        WriteStorage<'a, Confusion>,
+        WriteStorage<'a, Viewshed>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, WantsToMelee>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut map,
+            player_pos,
+            runstate,
+            companions,
+            factions,
+            mut confusions,
+            mut viewsheds,
+            mut positions,
+            mut wants_to_melees,
+        ) = data;
+
+        if *runstate != RunState::MonsterTurn {
+            return;
+        };
+
+        let others: Vec<(Entity, Faction, Point)> = (&entities, &factions, &positions)
+            .join()
+            .map(|(entity, faction, pos)| (entity, *faction, Point::new(pos.x, pos.y)))
+            .collect();
+
+        for (entity, viewshed, companion, faction, pos) in (
+            &entities,
+            &mut viewsheds,
+            &companions,
+            &factions,
+            &mut positions,
+        )
+            .join()
+        {
+            if let Some(confusion) = confusions.get_mut(entity) {
+                confusion.turns -= 1;
+                if confusion.turns == 0 {
+                    confusions
+                        .remove(entity)
+                        .expect("não consegui desconfundir!");
+                };
+                continue;
+            }
+
+            let my_pos = Point::new(pos.x, pos.y);
+            let nearest_enemy = others
+                .iter()
+                .filter(|(other, other_faction, other_pos)| {
+                    *other != entity
+                        && viewshed.visible_tiles.contains(other_pos)
+                        && reaction(*faction, *other_faction) == Reaction::Attack
+                })
+                .map(|(other, _, other_pos)| {
+                    let distance = rltk::DistanceAlg::Pythagoras.distance2d(my_pos, *other_pos);
+                    (*other, *other_pos, distance)
+                })
+                .min_by(|(_, _, d1), (_, _, d2)| d1.partial_cmp(d2).unwrap());
+
+            let destination = match (nearest_enemy, companion.order) {
+                (Some((target, _, distance)), _) if distance < 1.5 => {
+                    wants_to_melees
+                        .insert(entity, WantsToMelee { target })
+                        .expect("o companheiro não quis tretar!");
+                    None
+                }
+                (Some((_, target_pos, _)), CompanionOrder::Follow) => {
+                    step_towards(&mut map, my_pos, target_pos)
+                }
+                (None, CompanionOrder::Follow) => {
+                    let distance = rltk::DistanceAlg::Pythagoras.distance2d(my_pos, *player_pos);
+                    if distance > FOLLOW_DISTANCE {
+                        step_towards(&mut map, my_pos, *player_pos)
+                    } else {
+                        None
+                    }
+                }
+                (_, CompanionOrder::Stay) => None,
+            };
+
+            if let Some(destination) = destination {
+                let old_idx = map.xy_idx(pos.x as usize, pos.y as usize);
+                let new_idx = map.xy_idx(destination.x as usize, destination.y as usize);
+                map.blocked[old_idx] = false;
+                map.blocked[new_idx] = true;
+                pos.x = destination.x;
+                pos.y = destination.y;
+                viewshed.dirty = true;
+            }
+        }
+    }
+}
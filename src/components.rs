@@ -1,7 +1,10 @@
+use rltk::DiceType;
 use rltk::Point;
 use rltk::RGB;
 use specs::prelude::*;
 
+use crate::i18n::Text;
+
 pub struct Position {
     pub x: i32,
     pub y: i32,
@@ -19,6 +22,12 @@ pub struct Renderable {
     pub render_order: usize,
 }
 
+/// Efeito visual passageiro; some quando o tempo acaba.
+#[derive(Component)]
+pub struct ParticleLifetime {
+    pub lifetime_ms: f32,
+}
+
 #[derive(Component, Debug)]
 pub struct Player {}
 
@@ -34,18 +43,61 @@ pub struct Monster {}
 
 #[derive(Component, PartialEq, Clone)]
 pub struct Name {
-    pub name: String,
+    pub name: Text,
 }
 
 #[derive(Component)]
 pub struct BlocksTile {}
 
+#[derive(Component, Clone, Copy)]
+pub struct Attributes {
+    pub might: i32,
+    pub fitness: i32,
+    pub quickness: i32,
+    pub intelligence: i32,
+}
+
+#[derive(Component, Clone, Copy)]
+pub struct Skills {
+    pub melee: i32,
+    pub defense: i32,
+    pub magic: i32,
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub enum CharacterClass {
+    Warrior,
+    Mage,
+    Rogue,
+}
+
+impl CharacterClass {
+    pub fn name(&self) -> Text {
+        Text::message(match self {
+            CharacterClass::Warrior => "class.warrior",
+            CharacterClass::Mage => "class.mage",
+            CharacterClass::Rogue => "class.rogue",
+        })
+    }
+}
+
+#[derive(Component)]
+pub struct Progression {
+    pub level: i32,
+    pub xp: i32,
+}
+
 #[derive(Component)]
 pub struct CombatStats {
     pub max_hp: u32,
     pub hp: i32,
-    pub defense: u32,
-    pub power: u32,
+    pub armour_class: i32,
+}
+
+#[derive(Component, Clone, Copy)]
+pub struct NaturalAttack {
+    pub hit_bonus: i32,
+    pub damage: DiceType,
 }
 
 #[derive(Component)]
@@ -56,15 +108,23 @@ pub struct WantsToMelee {
 #[derive(Component)]
 pub struct SufferDamage {
     pub amount: Vec<u32>,
+    pub from_player: bool,
 }
 
 impl SufferDamage {
-    pub fn new_damage(store: &mut WriteStorage<SufferDamage>, victim: Entity, amount: u32) {
+    pub fn new_damage(
+        store: &mut WriteStorage<SufferDamage>,
+        victim: Entity,
+        amount: u32,
+        from_player: bool,
+    ) {
         if let Some(suffering) = store.get_mut(victim) {
             suffering.amount.push(amount);
+            suffering.from_player |= from_player;
         } else {
             let dmg = Self {
                 amount: vec![amount],
+                from_player,
             };
             store
                 .insert(victim, dmg)
@@ -76,6 +136,13 @@ impl SufferDamage {
 #[derive(Component)]
 pub struct Item {}
 
+/// Que item é, pelo ID do catálogo de mensagens; não muda com o idioma nem
+/// com a identificação, por isso serve para comparar itens.
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct ItemKind {
+    pub id: &'static str,
+}
+
 #[derive(Component)]
 pub struct ProvidesHealing {
     pub heal_amount: usize,
@@ -106,6 +173,26 @@ pub struct WantsToDropItem {
 #[derive(Component)]
 pub struct Consumable {}
 
+/// Peso de uma unidade do item, em kg.
+#[derive(Component)]
+pub struct Weight {
+    pub kg: f32,
+}
+
+/// Quanto a criatura carrega e quanto aguenta carregar, em kg; recalculado
+/// pelo `EncumbranceSystem`.
+#[derive(Component)]
+pub struct Burden {
+    pub load: f32,
+    pub capacity: f32,
+}
+
+/// Itens iguais se juntam numa pilha só dentro da mochila.
+#[derive(Component)]
+pub struct Stackable {
+    pub quantity: u32,
+}
+
 #[derive(Component)]
 pub struct InflictsDamage {
     pub damage: u32,
@@ -116,12 +203,127 @@ pub struct Ranged {
     pub range: u32,
 }
 
+/// Dano causado quando o item acerta alguém arremessado ou disparado.
+#[derive(Component)]
+pub struct Weapon {
+    pub hit_bonus: i32,
+    pub damage: DiceType,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AmmoKind {
+    Arrow,
+    Bolt,
+}
+
+#[derive(Component)]
+pub struct Ammunition {
+    pub kind: AmmoKind,
+}
+
+/// Arcos e bestas disparam a munição certa tirada da mochila.
+#[derive(Component)]
+pub struct Launcher {
+    pub ammo: AmmoKind,
+    pub range: u32,
+    pub hit_bonus: i32,
+}
+
+/// Arremessar `item` em direção a `target`; com `launcher`, o item é a
+/// munição disparada por ele.
+#[derive(Component)]
+pub struct WantsToThrowItem {
+    pub item: Entity,
+    pub target: Point,
+    pub launcher: Option<Entity>,
+}
+
+/// Formato da área: `Burst` é um disco e `Square` um quadrado em volta do
+/// alvo; `Cone` abre a partir de quem lança e `Beam` é um raio que atravessa
+/// tudo no caminho. Para cones e raios, `radius` é o comprimento.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AreaShape {
+    Burst,
+    Square,
+    Cone,
+    Beam,
+}
+
 #[derive(Component)]
 pub struct AreaOfEffect {
     pub radius: u32,
+    pub shape: AreaShape,
 }
 
 #[derive(Component)]
 pub struct Confusion {
     pub turns: u32,
 }
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Faction {
+    Player,
+    Goblins,
+    Orcs,
+    Critters,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CompanionOrder {
+    Follow,
+    Stay,
+}
+
+#[derive(Component)]
+pub struct Companion {
+    pub order: CompanionOrder,
+}
+
+/// Monstros sem este componente estão alertas.
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub enum Awareness {
+    Asleep,
+    Unaware,
+}
+
+#[derive(Component)]
+pub struct Mana {
+    pub current: i32,
+    pub max: i32,
+}
+
+#[derive(Component)]
+pub struct Spell {
+    pub mana_cost: i32,
+}
+
+#[derive(Component)]
+pub struct KnownSpells {
+    pub spells: Vec<Entity>,
+}
+
+#[derive(Component)]
+pub struct Charges {
+    pub current: u32,
+    pub max: u32,
+}
+
+#[derive(Component)]
+pub struct Recharges {}
+
+/// Nome mostrado enquanto o tipo do item não for identificado.
+#[derive(Component)]
+pub struct ObfuscatedName {
+    pub name: Text,
+}
+
+#[derive(Component)]
+pub struct Identifies {}
+
+#[derive(Component)]
+pub struct Cursed {
+    pub known: bool,
+}
+
+#[derive(Component)]
+pub struct RemovesCurse {}
@@ -1,30 +1,106 @@
 use rltk::console;
+use rltk::RandomNumberGenerator;
+use rltk::RGB;
 use specs::prelude::*;
 use specs::World;
 
+use crate::attributes;
 use crate::components::*;
 use crate::game_log::GameLog;
+use crate::game_log::LogCategory;
+use crate::i18n::Catalogue;
+use crate::i18n::Text;
+use crate::player::Resting;
+use crate::RunState;
 
 pub struct DamageSystem {}
 
 impl<'a> System<'a> for DamageSystem {
     type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, GameLog>,
+        WriteExpect<'a, RandomNumberGenerator>,
         WriteStorage<'a, CombatStats>,
         WriteStorage<'a, SufferDamage>,
+        WriteStorage<'a, Awareness>,
+        WriteStorage<'a, Progression>,
+        WriteStorage<'a, Attributes>,
+        ReadStorage<'a, Skills>,
+        WriteStorage<'a, Mana>,
+        ReadStorage<'a, Companion>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mut combat_stats, mut suffer_damages) = data;
+        let (
+            entities,
+            player_entity,
+            mut game_log,
+            mut rng,
+            mut combat_stats,
+            mut suffer_damages,
+            mut awarenesses,
+            mut progressions,
+            mut attributes,
+            skills,
+            mut manas,
+            companions,
+        ) = data;
 
-        for (mut stats, damage) in (&mut combat_stats, &suffer_damages).join() {
+        let mut xp_gained = 0;
+        for (entity, stats, damage) in (&entities, &mut combat_stats, &suffer_damages).join() {
+            let was_alive = stats.hp > 0;
             let total_damage: u32 = damage.amount.iter().sum();
             stats.hp -= total_damage as i32;
+            // apanhar acorda qualquer um
+            awarenesses.remove(entity);
+
+            // morrer pelas próprias mãos, ou matar um companheiro, não ensina nada
+            let worth_xp = entity != *player_entity && companions.get(entity).is_none();
+            if was_alive && stats.hp <= 0 && damage.from_player && worth_xp {
+                xp_gained += attributes::xp_reward(stats);
+            }
         }
 
         suffer_damages.clear();
+
+        if xp_gained == 0 {
+            return;
+        }
+
+        if let (Some(progression), Some(attributes), Some(skills), Some(stats)) = (
+            progressions.get_mut(*player_entity),
+            attributes.get_mut(*player_entity),
+            skills.get(*player_entity),
+            combat_stats.get_mut(*player_entity),
+        ) {
+            progression.xp += xp_gained;
+            while progression.xp >= progression.level * attributes::XP_PER_LEVEL {
+                progression.level += 1;
+                let raised = attributes::raise_attribute(attributes, rng.roll_dice(1, 4));
+                level_up_stats(stats, attributes, skills, progression.level);
+                if let Some(mana) = manas.get_mut(*player_entity) {
+                    mana.max = attributes::max_mana(attributes, skills, progression.level);
+                    mana.current = mana.max;
+                }
+                game_log
+                    .entry(LogCategory::Combat, "progression.level_up")
+                    .arg("level", progression.level)
+                    .arg("attribute", Text::message(raised))
+                    .log();
+            }
+        }
     }
 }
 
+/// Sobe a vida máxima e a defesa para o novo nível sem curar: quem sobe de
+/// nível machucado continua machucado.
+fn level_up_stats(stats: &mut CombatStats, attributes: &Attributes, skills: &Skills, level: i32) {
+    let new = attributes::combat_stats(attributes, skills, level);
+    stats.max_hp = new.max_hp;
+    stats.armour_class = new.armour_class;
+}
+
 pub fn delete_the_dead(ecs: &mut World) {
     let mut dead = Vec::new();
 
@@ -33,20 +109,35 @@ pub fn delete_the_dead(ecs: &mut World) {
         let player_entity = ecs.fetch::<Entity>();
         let entities = ecs.entities();
         let names = ecs.read_storage::<Name>();
+        let renderables = ecs.read_storage::<Renderable>();
         let mut game_logs = ecs.fetch_mut::<GameLog>();
+        let catalogue = ecs.fetch::<Catalogue>();
+        let mut runstate = ecs.write_resource::<RunState>();
+        let mut resting = ecs.fetch_mut::<Resting>();
 
         (&combat_stats, &entities)
             .join()
             .for_each(|(stats, entity)| {
                 if stats.hp <= 0 {
                     if entity == *player_entity {
+                        if *runstate == RunState::GameOver {
+                            return;
+                        }
+                        *runstate = RunState::GameOver;
+                        // morto não descansa
+                        *resting = Resting::default();
                         game_logs
-                            .entries
-                            .push("omae wa mou shinde iru.".to_string());
-                        console::log("omae wa mou shinde iru.")
+                            .entry(LogCategory::Combat, "death.player")
+                            .colored(RGB::named(rltk::RED))
+                            .log();
+                        game_logs.finish(&catalogue);
+                        console::log(catalogue.text("death.player"))
                     } else {
                         if let Some(n) = names.get(entity) {
-                            game_logs.entries.push(format!("{} morreu.", n.name));
+                            game_logs
+                                .entry(LogCategory::Combat, "death.creature")
+                                .name("name", &n.name, renderables.get(entity))
+                                .log();
                         }
                         dead.push(entity);
                     }
@@ -59,3 +150,43 @@ pub fn delete_the_dead(ecs: &mut World) {
             .expect("não consegui remover um morto!")
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spawner;
+
+    #[test]
+    fn killing_yourself_gives_no_xp() {
+        let (world, player) = spawner::test_world(5);
+        SufferDamage::new_damage(&mut world.write_storage(), player, 50, true);
+        DamageSystem {}.run_now(&world);
+
+        let stats = world.read_storage::<CombatStats>();
+        assert!(stats.get(player).unwrap().hp <= 0);
+        let progressions = world.read_storage::<Progression>();
+        assert_eq!(progressions.get(player).unwrap().xp, 0);
+    }
+
+    #[test]
+    fn levelling_up_keeps_current_hp() {
+        let (mut world, player) = spawner::test_world(3);
+        let goblin = world
+            .create_entity()
+            .with(CombatStats {
+                max_hp: 1000,
+                hp: 1,
+                armour_class: 10,
+            })
+            .build();
+        SufferDamage::new_damage(&mut world.write_storage(), goblin, 5, true);
+        DamageSystem {}.run_now(&world);
+
+        let progressions = world.read_storage::<Progression>();
+        assert!(progressions.get(player).unwrap().level > 1);
+        let stats = world.read_storage::<CombatStats>();
+        let stats = stats.get(player).unwrap();
+        assert_eq!(stats.hp, 3);
+        assert!(stats.max_hp > 20);
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use specs::prelude::*;
+
+use crate::attributes;
+use crate::attributes::Encumbrance;
+use crate::components::*;
+use crate::game_log::GameLog;
+
+/// Soma o peso de tudo o que cada criatura leva na mochila.
+pub struct EncumbranceSystem {}
+
+impl<'a> System<'a> for EncumbranceSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        WriteExpect<'a, GameLog>,
+        ReadStorage<'a, Attributes>,
+        ReadStorage<'a, InBackpack>,
+        ReadStorage<'a, Weight>,
+        ReadStorage<'a, Stackable>,
+        WriteStorage<'a, Burden>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player_entity,
+            mut game_log,
+            attributes,
+            in_backpacks,
+            weights,
+            stackables,
+            mut burdens,
+        ) = data;
+
+        let mut loads: HashMap<Entity, f32> = HashMap::new();
+        for (item, backpack, weight) in (&entities, &in_backpacks, &weights).join() {
+            let quantity = stackables.get(item).map_or(1, |s| s.quantity);
+            *loads.entry(backpack.owner).or_insert(0.0) += weight.kg * quantity as f32;
+        }
+
+        for (entity, attributes) in (&entities, &attributes).join() {
+            let load = loads.get(&entity).copied().unwrap_or(0.0);
+            let capacity = attributes::carry_capacity(attributes);
+            let before = burdens
+                .get(entity)
+                .map(|b| attributes::encumbrance(b.load, b.capacity));
+            let now = attributes::encumbrance(load, capacity);
+
+            if entity == *player_entity && before.is_some() && before != Some(now) {
+                game_log.item(match now {
+                    Encumbrance::Unburdened => "burden.unburdened",
+                    Encumbrance::Burdened => "burden.burdened",
+                    Encumbrance::Overloaded => "burden.overloaded",
+                });
+            }
+
+            burdens
+                .insert(entity, Burden { load, capacity })
+                .expect("não consegui pesar a mochila!");
+        }
+    }
+}
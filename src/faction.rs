@@ -0,0 +1,69 @@
+use crate::components::Faction;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Reaction {
+    Attack,
+    Ignore,
+    Flee,
+    Follow,
+}
+
+/// How an entity of faction `from` behaves towards one of faction `to`.
+pub fn reaction(from: Faction, to: Faction) -> Reaction {
+    use Faction::*;
+    use Reaction::*;
+
+    match (from, to) {
+        (Player, Player) => Follow,
+        (Player, Goblins) => Attack,
+        (Player, Orcs) => Attack,
+        (Player, Critters) => Ignore,
+
+        (Goblins, Player) => Attack,
+        (Goblins, Goblins) => Ignore,
+        (Goblins, Orcs) => Attack,
+        (Goblins, Critters) => Ignore,
+
+        (Orcs, Player) => Attack,
+        (Orcs, Goblins) => Attack,
+        (Orcs, Orcs) => Ignore,
+        (Orcs, Critters) => Attack,
+
+        (Critters, Critters) => Ignore,
+        (Critters, _) => Flee,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Faction; 4] = [
+        Faction::Player,
+        Faction::Goblins,
+        Faction::Orcs,
+        Faction::Critters,
+    ];
+
+    #[test]
+    fn never_attacks_own_faction() {
+        for faction in ALL.iter() {
+            assert_ne!(reaction(*faction, *faction), Reaction::Attack);
+        }
+    }
+
+    #[test]
+    fn critters_flee_from_everyone_else() {
+        for faction in ALL.iter().filter(|f| **f != Faction::Critters) {
+            assert_eq!(reaction(Faction::Critters, *faction), Reaction::Flee);
+        }
+    }
+
+    #[test]
+    fn player_and_monsters_are_mutually_hostile() {
+        for faction in [Faction::Goblins, Faction::Orcs].iter() {
+            assert_eq!(reaction(Faction::Player, *faction), Reaction::Attack);
+            assert_eq!(reaction(*faction, Faction::Player), Reaction::Attack);
+        }
+    }
+}
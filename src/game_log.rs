@@ -1,3 +1,290 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::Write;
+
+use rltk::RGB;
+
+use crate::components::Renderable;
+use crate::i18n;
+use crate::i18n::Catalogue;
+use crate::i18n::Piece;
+use crate::i18n::Text;
+use crate::i18n::ToText;
+
+/// Arquivo onde o registro é gravado ao fim do jogo, se o jogador pedir.
+pub const LOG_FILE_PATH: &str = "registro.txt";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LogCategory {
+    Combat,
+    Item,
+    System,
+}
+
+impl LogCategory {
+    pub fn name(self) -> Text {
+        Text::message(match self {
+            LogCategory::Combat => "log.category.combat",
+            LogCategory::Item => "log.category.item",
+            LogCategory::System => "log.category.system",
+        })
+    }
+}
+
+/// Pedaço de uma mensagem, todo de uma cor só.
+#[derive(Clone, PartialEq, Debug)]
+pub struct LogFragment {
+    pub color: RGB,
+    pub text: String,
+}
+
+/// Valor de um `{marcador}`, ainda sem traduzir.
+#[derive(Clone, PartialEq, Debug)]
+struct LogArg {
+    color: RGB,
+    text: Text,
+}
+
+/// Uma mensagem do registro, guardada pelo ID para sair no idioma atual.
+#[derive(PartialEq, Debug)]
+pub struct LogEntry {
+    pub turn: u32,
+    pub category: LogCategory,
+    id: &'static str,
+    color: RGB,
+    args: Vec<(&'static str, LogArg)>,
+    /// Quantas vezes seguidas a mesma mensagem apareceu.
+    pub repeats: u32,
+}
+
+impl LogEntry {
+    /// A mensagem traduzida, em pedaços coloridos.
+    pub fn fragments(&self, catalogue: &Catalogue) -> Vec<LogFragment> {
+        i18n::pieces(&catalogue.text(self.id))
+            .iter()
+            .map(|piece| match piece {
+                Piece::Literal(literal) => LogFragment {
+                    color: self.color,
+                    text: literal.to_string(),
+                },
+                Piece::Placeholder(key) => self
+                    .args
+                    .iter()
+                    .find(|(k, _arg)| k == key)
+                    .map(|(_key, arg)| LogFragment {
+                        color: arg.color,
+                        text: catalogue.render(&arg.text),
+                    })
+                    .unwrap_or_else(|| LogFragment {
+                        color: self.color,
+                        text: format!("{{{}}}", key),
+                    }),
+            })
+            .collect()
+    }
+
+    /// A mensagem sem cores, com o "x3" das repetições.
+    pub fn text(&self, catalogue: &Catalogue) -> String {
+        let mut text = self
+            .fragments(catalogue)
+            .iter()
+            .map(|fragment| fragment.text.as_str())
+            .collect::<String>();
+        if self.repeats > 1 {
+            text.push_str(&format!(" x{}", self.repeats));
+        }
+        text
+    }
+}
+
+/// Monta uma mensagem do catálogo, com cada `{marcador}` preenchido por um
+/// valor colorido; `log` a grava no registro.
+pub struct LogBuilder<'a> {
+    game_log: &'a mut GameLog,
+    category: LogCategory,
+    id: &'static str,
+    color: RGB,
+    args: Vec<(&'static str, LogArg)>,
+}
+
+impl<'a> LogBuilder<'a> {
+    /// Cor do texto fixo da mensagem; branco se não for escolhida.
+    pub fn colored(mut self, color: RGB) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn arg_colored<T: ToText>(mut self, key: &'static str, color: RGB, value: T) -> Self {
+        self.args.push((
+            key,
+            LogArg {
+                color,
+                text: value.to_text(),
+            },
+        ));
+        self
+    }
+
+    pub fn arg<T: ToText>(self, key: &'static str, value: T) -> Self {
+        self.arg_colored(key, RGB::named(rltk::WHITE), value)
+    }
+
+    /// Nome de uma criatura ou item, na cor em que ele aparece no mapa.
+    pub fn name<T: ToText>(
+        self,
+        key: &'static str,
+        name: T,
+        renderable: Option<&Renderable>,
+    ) -> Self {
+        let color = renderable.map_or(RGB::named(rltk::WHITE), |r| r.fg);
+        self.arg_colored(key, color, name)
+    }
+
+    pub fn damage<N: Display>(self, key: &'static str, amount: N) -> Self {
+        self.arg_colored(key, RGB::named(rltk::RED), amount)
+    }
+
+    pub fn healing<N: Display>(self, key: &'static str, amount: N) -> Self {
+        self.arg_colored(key, RGB::named(rltk::GREEN), amount)
+    }
+
+    pub fn log(self) {
+        let entry = LogEntry {
+            turn: self.game_log.turn,
+            category: self.category,
+            id: self.id,
+            color: self.color,
+            args: self.args,
+            repeats: 1,
+        };
+        self.game_log.push(entry);
+    }
+}
+
 pub struct GameLog {
-    pub entries: Vec<String>,
+    pub entries: Vec<LogEntry>,
+    /// Turnos completos desde o começo da partida.
+    pub turn: u32,
+    /// Gravar o registro inteiro em `LOG_FILE_PATH` quando o jogo acabar.
+    pub save_at_end: bool,
+}
+
+impl GameLog {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            turn: 0,
+            save_at_end: false,
+        }
+    }
+
+    /// Começa a mensagem `id` do catálogo.
+    pub fn entry(&mut self, category: LogCategory, id: &'static str) -> LogBuilder<'_> {
+        LogBuilder {
+            game_log: self,
+            category,
+            id,
+            color: RGB::named(rltk::WHITE),
+            args: Vec::new(),
+        }
+    }
+
+    /// Uma mensagem igual à anterior só aumenta a contagem de repetições.
+    fn push(&mut self, entry: LogEntry) {
+        if let Some(last) = self.entries.last_mut() {
+            if last.category == entry.category
+                && last.id == entry.id
+                && last.color == entry.color
+                && last.args == entry.args
+            {
+                last.repeats += 1;
+                last.turn = self.turn;
+                return;
+            }
+        }
+        self.entries.push(entry);
+    }
+
+    pub fn log(&mut self, category: LogCategory, id: &'static str) {
+        self.entry(category, id).log();
+    }
+
+    pub fn item(&mut self, id: &'static str) {
+        self.log(LogCategory::Item, id);
+    }
+
+    pub fn system(&mut self, id: &'static str) {
+        self.log(LogCategory::System, id);
+    }
+
+    /// No fim do jogo, grava o registro se o jogador pediu; só uma vez.
+    pub fn finish(&mut self, catalogue: &Catalogue) {
+        if !self.save_at_end {
+            return;
+        }
+        self.save_at_end = false;
+        match self.write_file(catalogue) {
+            Ok(()) => self
+                .entry(LogCategory::System, "log.saved")
+                .arg("path", LOG_FILE_PATH)
+                .log(),
+            Err(error) => self
+                .entry(LogCategory::System, "log.save_failed")
+                .arg("path", LOG_FILE_PATH)
+                .arg("error", error)
+                .log(),
+        }
+    }
+
+    fn write_file(&self, catalogue: &Catalogue) -> std::io::Result<()> {
+        let mut file = File::create(LOG_FILE_PATH)?;
+        for entry in self.entries.iter() {
+            writeln!(
+                file,
+                "{}",
+                catalogue.format(
+                    "log.file_line",
+                    &[
+                        ("turn", &entry.turn),
+                        ("category", &catalogue.render(&entry.category.name())),
+                        ("text", &entry.text(catalogue)),
+                    ]
+                )
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn goblin_misses(game_log: &mut GameLog) {
+        game_log
+            .entry(LogCategory::Combat, "melee.miss")
+            .name("attacker", "Goblin", None)
+            .name("target", "Fulano", None)
+            .log();
+    }
+
+    #[test]
+    fn repeated_messages_are_counted() {
+        let mut game_log = GameLog::new();
+        goblin_misses(&mut game_log);
+        goblin_misses(&mut game_log);
+        game_log.turn = 3;
+        goblin_misses(&mut game_log);
+        game_log.system("game.welcome");
+        goblin_misses(&mut game_log);
+
+        let catalogue = Catalogue::load(i18n::Language::Portuguese).0;
+        assert_eq!(game_log.entries.len(), 3);
+        assert_eq!(
+            game_log.entries[0].text(&catalogue),
+            "Goblin tenta acertar Fulano, mas erra. x3"
+        );
+        assert_eq!(game_log.entries[0].turn, 3);
+        assert_eq!(game_log.entries[2].repeats, 1);
+    }
 }
@@ -4,9 +4,29 @@ use rltk::VirtualKeyCode;
 use rltk::RGB;
 use specs::prelude::*;
 
+use crate::area_of_effect;
+use crate::attributes;
+use crate::attributes::Encumbrance;
+use crate::camera::Camera;
 use crate::components::*;
+use crate::faction::reaction;
+use crate::faction::Reaction;
 use crate::game_log::GameLog;
+use crate::game_log::LogCategory;
+use crate::game_log::LogEntry;
+use crate::i18n::Catalogue;
+use crate::i18n::Text;
+use crate::identification::ItemKnowledge;
+use crate::keybindings::Action;
+use crate::keybindings::KeyContext;
+use crate::keybindings::KeyMap;
+use crate::layout::Layout;
+use crate::layout::Region;
 use crate::map::Map;
+use crate::map::TileType;
+use crate::tileset::RenderMode;
+use crate::tileset::ASCII_CONSOLE;
+use crate::tileset::TILE_CONSOLE;
 use crate::RunState;
 use crate::State;
 
@@ -18,10 +38,33 @@ pub enum ItemMenuResult {
     RangeSelected(Point),
 }
 
+#[derive(PartialEq)]
+pub enum MenuResult {
+    Cancel,
+    NoResponse,
+}
+
+#[derive(PartialEq)]
+pub enum CharacterCreationResult {
+    Cancel,
+    NoResponse,
+    Done,
+}
+
+/// Personagem sendo montado na tela de criação.
+pub struct CharacterDraft {
+    pub class: CharacterClass,
+    pub name: String,
+}
+
+const MAX_NAME_LENGTH: usize = 20;
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum MainMenuSelection {
     NewGame,
     Load,
+    Language,
+    Settings,
     Quit,
 }
 
@@ -32,79 +75,342 @@ pub enum MainMenuResult {
 }
 
 pub fn draw_ui(ecs: &World, ctx: &mut Rltk) {
-    let map = ecs.fetch::<Map>();
-    let map_width = map.width;
-    let map_height = map.height;
-    let start_x = 0;
-    let start_y = map_height;
-    let height = 6;
-    let width = map_width - 1;
-    let fg = RGB::named(rltk::WHITE);
-    let bg = RGB::named(rltk::BLACK);
+    let layout = Layout::default();
+    draw_log_panel(ecs, ctx, layout.log);
+    draw_sidebar(ecs, ctx, layout.sidebar);
 
-    ctx.draw_box(start_x, start_y, width, height, fg, bg);
+    let mouse_pos = ctx.mouse_pos();
+    ctx.set_bg(mouse_pos.0, mouse_pos.1, RGB::named(rltk::MAGENTA));
 
+    draw_tooltips(ecs, ctx);
+}
+
+fn draw_box_around(ctx: &mut Rltk, region: Region) {
+    ctx.draw_box(
+        region.x,
+        region.y,
+        region.width - 1,
+        region.height - 1,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+    );
+}
+
+/// As últimas mensagens, a mais nova em cima.
+fn draw_log_panel(ecs: &World, ctx: &mut Rltk, region: Region) {
+    draw_box_around(ctx, region);
+
+    let game_log = ecs.fetch::<GameLog>();
+    let catalogue = ecs.fetch::<Catalogue>();
+    let rows = (region.height - 2) as usize;
+    for (i, entry) in game_log.entries.iter().rev().take(rows).enumerate() {
+        print_log_entry(
+            ctx,
+            region.x + 2,
+            region.y + 1 + i as i32,
+            entry,
+            &catalogue,
+        );
+    }
+}
+
+/// Escreve a barra lateral linha por linha, parando quando acaba o espaço.
+struct Sidebar<'a> {
+    ctx: &'a mut Rltk,
+    region: Region,
+    y: i32,
+}
+
+impl Sidebar<'_> {
+    const NAME_WIDTH: usize = 12;
+
+    fn has_room(&self) -> bool {
+        self.y < self.region.y + self.region.height - 1
+    }
+
+    fn line<S: ToString>(&mut self, fg: RGB, text: S) {
+        if self.has_room() {
+            let text = text
+                .to_string()
+                .chars()
+                .take(self.region.width as usize - 2)
+                .collect::<String>();
+            self.ctx
+                .print_color(self.region.x + 1, self.y, fg, RGB::named(rltk::BLACK), text);
+        }
+        self.y += 1;
+    }
+
+    fn heading(&mut self, text: String) {
+        self.y += 1;
+        self.line(RGB::named(rltk::YELLOW), text);
+    }
+
+    fn bar(&mut self, current: i32, max: i32, color: RGB) {
+        if self.has_room() {
+            self.ctx.draw_bar_horizontal(
+                self.region.x + 1,
+                self.y,
+                self.region.width - 2,
+                current,
+                max,
+                color,
+                RGB::named(rltk::BLACK),
+            );
+        }
+        self.y += 1;
+    }
+
+    /// Nome curto seguido de uma barra de vida na mesma linha.
+    fn named_bar(&mut self, name: &str, fg: RGB, current: i32, max: i32) {
+        if self.has_room() {
+            let name = name.chars().take(Self::NAME_WIDTH).collect::<String>();
+            let x = self.region.x + 1;
+            self.ctx
+                .print_color(x, self.y, fg, RGB::named(rltk::BLACK), name);
+            self.ctx.draw_bar_horizontal(
+                x + Self::NAME_WIDTH as i32 + 1,
+                self.y,
+                self.region.width - Self::NAME_WIDTH as i32 - 3,
+                current,
+                max,
+                RGB::named(rltk::RED),
+                RGB::named(rltk::BLACK),
+            );
+        }
+        self.y += 1;
+    }
+}
+
+fn draw_sidebar(ecs: &World, ctx: &mut Rltk, region: Region) {
+    draw_box_around(ctx, region);
+
+    let player_entity = *ecs.fetch::<Entity>();
+    let player_pos = *ecs.fetch::<Point>();
+    let map = ecs.fetch::<Map>();
+    let game_log = ecs.fetch::<GameLog>();
+    let catalogue = ecs.fetch::<Catalogue>();
+    let knowledge = ecs.fetch::<ItemKnowledge>();
     let combat_stats = ecs.read_storage::<CombatStats>();
-    let players = ecs.read_storage::<Player>();
+    let manas = ecs.read_storage::<Mana>();
+    let confusions = ecs.read_storage::<Confusion>();
+    let burdens = ecs.read_storage::<Burden>();
+    let names = ecs.read_storage::<Name>();
+    let kinds = ecs.read_storage::<ItemKind>();
+    let obfuscated_names = ecs.read_storage::<ObfuscatedName>();
+    let backpacks = ecs.read_storage::<InBackpack>();
+    let weapons = ecs.read_storage::<Weapon>();
+    let launchers = ecs.read_storage::<Launcher>();
+    let stackables = ecs.read_storage::<Stackable>();
+    let companions = ecs.read_storage::<Companion>();
+    let monsters = ecs.read_storage::<Monster>();
+    let positions = ecs.read_storage::<Position>();
+    let renderables = ecs.read_storage::<Renderable>();
+    let entities = ecs.entities();
 
-    for (_player, stats) in (&players, &combat_stats).join() {
-        let health = format!("HP: {} / {}", stats.hp, stats.max_hp);
-        // FIXME: relative coords
-        ctx.print_color(
-            12,
-            43,
+    let mut sidebar = Sidebar {
+        ctx,
+        region,
+        y: region.y + 1,
+    };
+    let white = RGB::named(rltk::WHITE);
+
+    if let Some(stats) = combat_stats.get(player_entity) {
+        sidebar.line(
             RGB::named(rltk::YELLOW),
-            RGB::named(rltk::BLACK),
-            health,
+            catalogue.format("hud.hp", &[("hp", &stats.hp), ("max", &stats.max_hp)]),
         );
-        ctx.draw_bar_horizontal(
-            28,
-            43,
-            51,
-            stats.hp,
-            stats.max_hp,
-            RGB::named(rltk::RED),
-            RGB::named(rltk::BLACK),
+        sidebar.bar(stats.hp, stats.max_hp as i32, RGB::named(rltk::RED));
+    }
+    if let Some(mana) = manas.get(player_entity) {
+        sidebar.line(
+            RGB::named(rltk::CYAN),
+            catalogue.format("hud.mp", &[("mp", &mana.current), ("max", &mana.max)]),
         );
+        sidebar.bar(mana.current, mana.max, RGB::named(rltk::BLUE));
     }
 
-    let game_log = ecs.fetch::<GameLog>();
-    for (i, entry) in game_log.entries.iter().rev().enumerate() {
-        let y = 44 + i;
-        if y < 49 {
-            ctx.print(2, y, entry);
+    sidebar.y += 1;
+    sidebar.line(
+        white,
+        catalogue.format("hud.depth", &[("depth", &map.depth)]),
+    );
+    sidebar.line(
+        white,
+        catalogue.format("hud.turn", &[("turn", &game_log.turn)]),
+    );
+
+    sidebar.heading(catalogue.text("hud.status"));
+    let mut effects = Vec::new();
+    if confusions.get(player_entity).is_some() {
+        effects.push((RGB::named(rltk::MAGENTA), "hud.status.confused"));
+    }
+    match burdens
+        .get(player_entity)
+        .map(|b| attributes::encumbrance(b.load, b.capacity))
+    {
+        Some(Encumbrance::Burdened) => {
+            effects.push((RGB::named(rltk::ORANGE), "hud.status.burdened"))
         }
+        Some(Encumbrance::Overloaded) => {
+            effects.push((RGB::named(rltk::RED), "hud.status.overloaded"))
+        }
+        _ => {}
+    }
+    if effects.is_empty() {
+        sidebar.line(RGB::named(rltk::GREY), catalogue.text("hud.status.none"));
+    }
+    for (fg, id) in effects {
+        sidebar.line(fg, catalogue.text(id));
     }
 
-    let mouse_pos = ctx.mouse_pos();
-    ctx.set_bg(mouse_pos.0, mouse_pos.1, RGB::named(rltk::MAGENTA));
+    sidebar.heading(catalogue.text("hud.carried_weapons"));
+    let mut carried = 0;
+    for (entity, name, backpack, stackable) in
+        (&entities, &names, &backpacks, stackables.maybe()).join()
+    {
+        if backpack.owner != player_entity
+            || (weapons.get(entity).is_none() && launchers.get(entity).is_none())
+        {
+            continue;
+        }
+        let name = with_quantity(
+            &catalogue,
+            catalogue.render(&knowledge.name_of(
+                name,
+                kinds.get(entity),
+                obfuscated_names.get(entity),
+            )),
+            stackable,
+        );
+        sidebar.line(white, name);
+        carried += 1;
+    }
+    if carried == 0 {
+        sidebar.line(
+            RGB::named(rltk::GREY),
+            catalogue.text("hud.carried_weapons.none"),
+        );
+    }
 
-    draw_tooltips(ecs, ctx);
+    let party = (&companions, &combat_stats, &names)
+        .join()
+        .collect::<Vec<_>>();
+    if !party.is_empty() {
+        sidebar.heading(catalogue.text("hud.companions"));
+        for (companion, stats, name) in party {
+            let fg = match companion.order {
+                CompanionOrder::Follow => white,
+                CompanionOrder::Stay => RGB::named(rltk::GREY),
+            };
+            sidebar.named_bar(
+                &catalogue.render(&name.name),
+                fg,
+                stats.hp,
+                stats.max_hp as i32,
+            );
+        }
+    }
+
+    let mut in_sight = (
+        &monsters,
+        &positions,
+        &combat_stats,
+        &names,
+        renderables.maybe(),
+    )
+        .join()
+        .filter(|(_monster, pos, _stats, _name, _render)| {
+            map.visible_tiles[map.xy_idx(pos.x as usize, pos.y as usize)]
+        })
+        .map(|(_monster, pos, stats, name, render)| {
+            let distance =
+                rltk::DistanceAlg::Pythagoras.distance2d(player_pos, Point::new(pos.x, pos.y));
+            (distance, stats, name, render)
+        })
+        .collect::<Vec<_>>();
+    in_sight.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    if !in_sight.is_empty() {
+        sidebar.heading(catalogue.text("hud.in_sight"));
+        for (_distance, stats, name, render) in in_sight {
+            let fg = render.map_or(white, |r| r.fg);
+            sidebar.named_bar(
+                &catalogue.render(&name.name),
+                fg,
+                stats.hp,
+                stats.max_hp as i32,
+            );
+        }
+    }
 }
 
 fn draw_tooltips(ecs: &World, ctx: &mut Rltk) {
-    let map = ecs.fetch::<Map>();
     let mouse_pos = ctx.mouse_pos();
-    if mouse_pos.0 >= map.width as i32 || mouse_pos.1 >= map.height as i32 {
-        return;
+    if let Some(world) = Camera::from_ecs(ecs).to_world(mouse_pos) {
+        draw_tooltips_at(ctx, mouse_pos, describe_tile(ecs, world));
+    }
+}
+
+/// O que o jogador enxerga na célula: o nome e o estado de cada coisa ali.
+fn describe_tile(ecs: &World, at: Point) -> Vec<String> {
+    let map = ecs.fetch::<Map>();
+    if !map.in_bounds(at.x, at.y) {
+        return Vec::new();
     }
 
     let mut tooltips = Vec::new();
     let names = ecs.read_storage::<Name>();
     let positions = ecs.read_storage::<Position>();
-    for (name, position) in (&names, &positions).join() {
+    let awarenesses = ecs.read_storage::<Awareness>();
+    let confusions = ecs.read_storage::<Confusion>();
+    let kinds = ecs.read_storage::<ItemKind>();
+    let obfuscated_names = ecs.read_storage::<ObfuscatedName>();
+    let knowledge = ecs.fetch::<ItemKnowledge>();
+    let catalogue = ecs.fetch::<Catalogue>();
+    for (name, position, awareness, confusion, kind, obfuscated) in (
+        &names,
+        &positions,
+        awarenesses.maybe(),
+        confusions.maybe(),
+        kinds.maybe(),
+        obfuscated_names.maybe(),
+    )
+        .join()
+    {
         let idx = map.xy_idx(position.x as usize, position.y as usize);
-        if position.x == mouse_pos.0 && position.y == mouse_pos.1 && map.visible_tiles[idx] {
-            tooltips.push(name.name.to_string());
+        if position.x == at.x && position.y == at.y && map.visible_tiles[idx] {
+            let mut name = catalogue.render(&knowledge.name_of(name, kind, obfuscated));
+            match awareness {
+                Some(Awareness::Asleep) => {
+                    name = catalogue.format("status.asleep", &[("name", &name)])
+                }
+                Some(Awareness::Unaware) => {
+                    name = catalogue.format("status.unaware", &[("name", &name)])
+                }
+                None => {}
+            }
+            if confusion.is_some() {
+                name = catalogue.format("status.confused", &[("name", &name)]);
+            }
+            tooltips.push(name);
         }
     }
+    tooltips
+}
+
+/// Desenha as dicas ao lado da posição `at` da tela.
+fn draw_tooltips_at(ctx: &mut Rltk, at: (i32, i32), tooltips: Vec<String>) {
+    let view = Layout::default().map;
+    if !view.contains(at.0, at.1) {
+        return;
+    }
 
     if !tooltips.is_empty() {
         let width = 3 + tooltips.iter().max_by_key(|s| s.len()).unwrap().len();
-        if mouse_pos.0 > 40 {
-            let arrow_pos = Point::new(mouse_pos.0 - 2, mouse_pos.1);
-            let left_x = mouse_pos.0 - width as i32;
-            let y = mouse_pos.1;
+        if at.0 > view.x + view.width / 2 {
+            let arrow_pos = Point::new(at.0 - 2, at.1);
+            let left_x = at.0 - width as i32;
+            let y = at.1;
             for (i, tooltip) in tooltips.iter().enumerate() {
                 ctx.print_color(
                     left_x,
@@ -132,9 +438,9 @@ fn draw_tooltips(ecs: &World, ctx: &mut Rltk) {
                 );
             }
         } else {
-            let arrow_pos = Point::new(mouse_pos.0 + 1, mouse_pos.1);
-            let left_x = mouse_pos.0 + 4 as i32;
-            let y = mouse_pos.1;
+            let arrow_pos = Point::new(at.0 + 1, at.1);
+            let left_x = at.0 + 4;
+            let y = at.1;
             for (i, tooltip) in tooltips.iter().enumerate() {
                 ctx.print_color(
                     left_x,
@@ -166,17 +472,26 @@ fn draw_tooltips(ecs: &World, ctx: &mut Rltk) {
 }
 
 pub fn show_inventory(gs: &mut State, ctx: &mut Rltk) -> ItemMenuResult {
+    let keymap = gs.ecs.fetch::<KeyMap>();
     let entities = gs.ecs.entities();
     let player_entity = gs.ecs.fetch::<Entity>();
     let names = gs.ecs.read_storage::<Name>();
     let in_backpacks = gs.ecs.read_storage::<InBackpack>();
+    let charges = gs.ecs.read_storage::<Charges>();
+    let kinds = gs.ecs.read_storage::<ItemKind>();
+    let obfuscated_names = gs.ecs.read_storage::<ObfuscatedName>();
+    let curses = gs.ecs.read_storage::<Cursed>();
+    let stackables = gs.ecs.read_storage::<Stackable>();
+    let burdens = gs.ecs.read_storage::<Burden>();
+    let knowledge = gs.ecs.fetch::<ItemKnowledge>();
+    let catalogue = gs.ecs.fetch::<Catalogue>();
 
     let inventory = (&entities, &names, &in_backpacks)
         .join()
         .filter(|(_entity, _name, backpack)| backpack.owner == *player_entity);
     let count = inventory.clone().count();
 
-    let mut y = (25 - (count / 2)) as i32;
+    let y = (25 - (count / 2)) as i32;
     ctx.draw_box(
         15,
         y - 2,
@@ -190,18 +505,28 @@ pub fn show_inventory(gs: &mut State, ctx: &mut Rltk) -> ItemMenuResult {
         y - 2,
         RGB::named(rltk::YELLOW),
         RGB::named(rltk::BLACK),
-        "Inventário",
+        match burdens.get(*player_entity) {
+            Some(burden) => catalogue.format(
+                "menu.inventory.title_with_load",
+                &[
+                    ("load", &format!("{:.1}", burden.load)),
+                    ("capacity", &format!("{:.1}", burden.capacity)),
+                ],
+            ),
+            None => catalogue.text("menu.inventory.title"),
+        },
     );
     ctx.print_color(
         18,
         y + count as i32 + 1,
         RGB::named(rltk::YELLOW),
         RGB::named(rltk::BLACK),
-        "ESCAPE para cancelar",
+        catalogue.text("menu.cancel_footer"),
     );
 
-    let mut equipabble: Vec<(Entity, &str)> = Vec::new();
+    let mut equipabble: Vec<(Entity, String)> = Vec::new();
     for (j, (entity, name, _backpack)) in inventory.enumerate() {
+        let y = y + j as i32;
         ctx.set(
             17,
             y,
@@ -226,23 +551,44 @@ pub fn show_inventory(gs: &mut State, ctx: &mut Rltk) -> ItemMenuResult {
             rltk::to_cp437(')'),
         );
 
-        ctx.print(21, y, &name.name.to_string());
-
-        equipabble.push((entity, &name.name));
+        let name = with_quantity(
+            &catalogue,
+            with_curse(
+                &catalogue,
+                catalogue.render(&knowledge.name_of(
+                    name,
+                    kinds.get(entity),
+                    obfuscated_names.get(entity),
+                )),
+                curses.get(entity),
+            ),
+            stackables.get(entity),
+        );
+        match charges.get(entity) {
+            Some(Charges { current, max }) => ctx.print(
+                21,
+                y,
+                catalogue.format(
+                    "status.charges",
+                    &[("name", &name), ("current", current), ("max", max)],
+                ),
+            ),
+            None => ctx.print(21, y, &name),
+        }
 
-        y += 1;
+        equipabble.push((entity, name));
     }
 
     match ctx.key {
         None => ItemMenuResult::NoResponse,
         Some(key) => {
-            if key == VirtualKeyCode::Escape {
+            if keymap.action(KeyContext::Menu, Some(key)) == Some(Action::Cancel) {
                 ItemMenuResult::Cancel
             } else {
                 let selection = rltk::letter_to_option(key);
                 if -1 < selection && selection < count as i32 {
-                    let (item_entity, name_str) = equipabble[selection as usize];
-                    ItemMenuResult::Selected((item_entity, name_str.to_string()))
+                    let (item_entity, name_str) = &equipabble[selection as usize];
+                    ItemMenuResult::Selected((*item_entity, name_str.to_string()))
                 } else {
                     ItemMenuResult::NoResponse
                 }
@@ -251,23 +597,57 @@ pub fn show_inventory(gs: &mut State, ctx: &mut Rltk) -> ItemMenuResult {
     }
 }
 
+fn with_curse(catalogue: &Catalogue, name: String, curse: Option<&Cursed>) -> String {
+    match curse {
+        Some(Cursed { known: true }) => catalogue.format("status.cursed", &[("name", &name)]),
+        _ => name,
+    }
+}
+
+fn with_quantity(catalogue: &Catalogue, name: String, stack: Option<&Stackable>) -> String {
+    match stack {
+        Some(Stackable { quantity }) if *quantity > 1 => catalogue.format(
+            "status.quantity",
+            &[("quantity", quantity), ("name", &name)],
+        ),
+        _ => name,
+    }
+}
+
 pub fn drop_item_menu(gs: &mut State, ctx: &mut Rltk) -> ItemMenuResult {
+    backpack_menu(gs, ctx, "menu.drop.title", "menu.drop.footer")
+}
+
+pub fn throw_item_menu(gs: &mut State, ctx: &mut Rltk) -> ItemMenuResult {
+    backpack_menu(gs, ctx, "menu.throw.title", "menu.cancel_footer")
+}
+
+/// Lista simples da mochila do jogador para escolher um item; `title` e
+/// `footer` são IDs do catálogo de mensagens.
+fn backpack_menu(gs: &mut State, ctx: &mut Rltk, title: &str, footer: &str) -> ItemMenuResult {
+    let keymap = gs.ecs.fetch::<KeyMap>();
     let player_entity = gs.ecs.fetch::<Entity>();
     let names = gs.ecs.read_storage::<Name>();
     let backpacks = gs.ecs.read_storage::<InBackpack>();
+    let kinds = gs.ecs.read_storage::<ItemKind>();
+    let obfuscated_names = gs.ecs.read_storage::<ObfuscatedName>();
+    let curses = gs.ecs.read_storage::<Cursed>();
+    let stackables = gs.ecs.read_storage::<Stackable>();
+    let knowledge = gs.ecs.fetch::<ItemKnowledge>();
+    let catalogue = gs.ecs.fetch::<Catalogue>();
     let entities = gs.ecs.entities();
 
     let inventory = (&entities, &backpacks, &names)
         .join()
         .filter(|(_entity, backpack, _name)| backpack.owner == *player_entity);
     let count = inventory.clone().count() as i32;
-    let y = (25 - (count / 2)) as i32;
+    let y = 25 - (count / 2);
 
     ctx.draw_box(
         15,
         y - 2,
         31,
-        count as i32 + 3,
+        count + 3,
         RGB::named(rltk::WHITE),
         RGB::named(rltk::BLACK),
     );
@@ -276,14 +656,14 @@ pub fn drop_item_menu(gs: &mut State, ctx: &mut Rltk) -> ItemMenuResult {
         y - 2,
         RGB::named(rltk::YELLOW),
         RGB::named(rltk::BLACK),
-        "Largar o quê?",
+        catalogue.text(title),
     );
     ctx.print_color(
         18,
         y + count + 1,
         RGB::named(rltk::YELLOW),
         RGB::named(rltk::BLACK),
-        "ESCAPE para cancelar",
+        catalogue.text(footer),
     );
 
     let mut equipable = Vec::new();
@@ -311,16 +691,31 @@ pub fn drop_item_menu(gs: &mut State, ctx: &mut Rltk) -> ItemMenuResult {
             rltk::to_cp437(')'),
         );
 
-        ctx.print(21, y + j as i32, &name.name.to_string());
-        equipable.push((entity, &name.name));
+        let name = with_quantity(
+            &catalogue,
+            with_curse(
+                &catalogue,
+                catalogue.render(&knowledge.name_of(
+                    name,
+                    kinds.get(entity),
+                    obfuscated_names.get(entity),
+                )),
+                curses.get(entity),
+            ),
+            stackables.get(entity),
+        );
+        ctx.print(21, y + j as i32, &name);
+        equipable.push((entity, name));
     }
 
     match ctx.key {
         None => ItemMenuResult::NoResponse,
-        Some(VirtualKeyCode::Escape) => ItemMenuResult::Cancel,
+        Some(key) if keymap.action(KeyContext::Menu, Some(key)) == Some(Action::Cancel) => {
+            ItemMenuResult::Cancel
+        }
         Some(key) => {
             let selection = rltk::letter_to_option(key);
-            if selection > -1 && selection < count as i32 {
+            if selection > -1 && selection < count {
                 let (item_entity, name) = &equipable[selection as usize];
                 ItemMenuResult::Selected((*item_entity, name.to_string()))
             } else {
@@ -330,17 +725,229 @@ pub fn drop_item_menu(gs: &mut State, ctx: &mut Rltk) -> ItemMenuResult {
     }
 }
 
-pub fn ranged_target(gs: &mut State, ctx: &mut Rltk, range: u32) -> ItemMenuResult {
+pub fn cast_spell_menu(gs: &mut State, ctx: &mut Rltk) -> ItemMenuResult {
+    let keymap = gs.ecs.fetch::<KeyMap>();
+    let player_entity = gs.ecs.fetch::<Entity>();
+    let names = gs.ecs.read_storage::<Name>();
+    let spells = gs.ecs.read_storage::<Spell>();
+    let known_spells = gs.ecs.read_storage::<KnownSpells>();
+    let manas = gs.ecs.read_storage::<Mana>();
+    let catalogue = gs.ecs.fetch::<Catalogue>();
+
+    let known = known_spells
+        .get(*player_entity)
+        .map_or(Vec::new(), |known| known.spells.clone());
+    let mana = manas.get(*player_entity).map_or(0, |mana| mana.current);
+    let count = known.len() as i32;
+    let y = 25 - (count / 2);
+
+    ctx.draw_box(
+        15,
+        y - 2,
+        31,
+        count + 3,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+    );
+    ctx.print_color(
+        18,
+        y - 2,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        catalogue.text("menu.spells.title"),
+    );
+    ctx.print_color(
+        18,
+        y + count + 1,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        catalogue.text("menu.cancel_footer"),
+    );
+
+    let letter_a = 'a' as u16;
+    for (j, spell_entity) in known.iter().enumerate() {
+        let y = y + j as i32;
+        let cost = spells.get(*spell_entity).map_or(0, |spell| spell.mana_cost);
+        let fg = if cost <= mana {
+            RGB::named(rltk::WHITE)
+        } else {
+            RGB::named(rltk::GREY)
+        };
+        ctx.set(
+            17,
+            y,
+            RGB::named(rltk::WHITE),
+            RGB::named(rltk::BLACK),
+            rltk::to_cp437('('),
+        );
+        ctx.set(
+            18,
+            y,
+            RGB::named(rltk::YELLOW),
+            RGB::named(rltk::BLACK),
+            letter_a + j as rltk::FontCharType,
+        );
+        ctx.set(
+            19,
+            y,
+            RGB::named(rltk::WHITE),
+            RGB::named(rltk::BLACK),
+            rltk::to_cp437(')'),
+        );
+        if let Some(name) = names.get(*spell_entity) {
+            ctx.print_color(
+                21,
+                y,
+                fg,
+                RGB::named(rltk::BLACK),
+                catalogue.render(&name.name),
+            );
+        }
+        ctx.print_color(
+            38,
+            y,
+            RGB::named(rltk::CYAN),
+            RGB::named(rltk::BLACK),
+            catalogue.format("menu.spells.cost", &[("cost", &format!("{:>2}", cost))]),
+        );
+    }
+
+    match ctx.key {
+        None => ItemMenuResult::NoResponse,
+        Some(key) if keymap.action(KeyContext::Menu, Some(key)) == Some(Action::Cancel) => {
+            ItemMenuResult::Cancel
+        }
+        Some(key) => {
+            let selection = rltk::letter_to_option(key);
+            if selection > -1 && selection < count {
+                let spell_entity = known[selection as usize];
+                let name = names
+                    .get(spell_entity)
+                    .map_or(Text::Literal(String::new()), |name| name.name.clone());
+                let cost = spells.get(spell_entity).map_or(0, |spell| spell.mana_cost);
+                if cost > mana {
+                    // sem mana nem chega a mirar, e o turno não é gasto
+                    gs.ecs
+                        .fetch_mut::<GameLog>()
+                        .entry(LogCategory::Item, "use.no_mana")
+                        .name("spell", &name, None)
+                        .log();
+                    return ItemMenuResult::NoResponse;
+                }
+                ItemMenuResult::Selected((spell_entity, catalogue.render(&name)))
+            } else {
+                ItemMenuResult::NoResponse
+            }
+        }
+    }
+}
+
+/// Cursor da mira e do modo de olhar. Anda com as mesmas teclas do
+/// movimento e acompanha o mouse sempre que ele se mexe.
+pub struct Cursor {
+    pub position: Point,
+    last_mouse: (i32, i32),
+}
+
+impl Cursor {
+    pub fn new() -> Self {
+        Self {
+            position: Point::new(0, 0),
+            last_mouse: (-1, -1),
+        }
+    }
+}
+
+/// Inimigos que o jogador enxerga, do mais perto para o mais longe.
+pub fn visible_hostiles(ecs: &World) -> Vec<Point> {
+    let player_entity = ecs.fetch::<Entity>();
+    let player_pos = ecs.fetch::<Point>();
+    let viewsheds = ecs.read_storage::<Viewshed>();
+    let factions = ecs.read_storage::<Faction>();
+    let positions = ecs.read_storage::<Position>();
+
+    let visible = match viewsheds.get(*player_entity) {
+        Some(viewshed) => &viewshed.visible_tiles,
+        None => return Vec::new(),
+    };
+    let mut hostiles = (&factions, &positions)
+        .join()
+        .filter(|(faction, _pos)| reaction(Faction::Player, **faction) == Reaction::Attack)
+        .map(|(_faction, pos)| Point::new(pos.x, pos.y))
+        .filter(|p| visible.contains(p))
+        .collect::<Vec<_>>();
+    hostiles.sort_by(|a, b| {
+        let da = rltk::DistanceAlg::Pythagoras.distance2d(*player_pos, *a);
+        let db = rltk::DistanceAlg::Pythagoras.distance2d(*player_pos, *b);
+        da.partial_cmp(&db).unwrap()
+    });
+    hostiles
+}
+
+/// Põe o cursor no inimigo visível mais próximo ou, sem nenhum, no jogador.
+/// O mouse só volta a mandar no cursor quando sair de `mouse_pos`.
+pub fn reset_cursor(ecs: &World, on_hostile: bool, mouse_pos: (i32, i32)) {
+    let player_pos = *ecs.fetch::<Point>();
+    let position = if on_hostile {
+        visible_hostiles(ecs).first().copied().unwrap_or(player_pos)
+    } else {
+        player_pos
+    };
+    let mut cursor = ecs.fetch_mut::<Cursor>();
+    cursor.position = position;
+    cursor.last_mouse = mouse_pos;
+}
+
+/// Atualiza o cursor com o mouse, as teclas de movimento e TAB, que passa
+/// para o próximo inimigo visível.
+fn move_cursor(ecs: &World, ctx: &Rltk) -> Point {
+    let hostiles = visible_hostiles(ecs);
+    let camera = Camera::from_ecs(ecs);
+    let map = ecs.fetch::<Map>();
+    let keymap = ecs.fetch::<KeyMap>();
+    let mut cursor = ecs.fetch_mut::<Cursor>();
+
+    let mouse_pos = ctx.mouse_pos();
+    if mouse_pos != cursor.last_mouse || ctx.left_click {
+        cursor.last_mouse = mouse_pos;
+        if let Some(world) = camera.to_world(mouse_pos) {
+            cursor.position = world;
+        }
+    }
+
+    if let Some(key) = ctx.key {
+        let action = keymap.action(KeyContext::Targeting, Some(key));
+        if let Some((dx, dy)) = action.and_then(Action::direction) {
+            cursor.position.x = (cursor.position.x + dx).clamp(0, map.width as i32 - 1);
+            cursor.position.y = (cursor.position.y + dy).clamp(0, map.height as i32 - 1);
+        } else if action == Some(Action::NextTarget) && !hostiles.is_empty() {
+            let next = match hostiles.iter().position(|p| *p == cursor.position) {
+                Some(i) => (i + 1) % hostiles.len(),
+                None => 0,
+            };
+            cursor.position = hostiles[next];
+        }
+    }
+
+    cursor.position
+}
+
+pub fn ranged_target(gs: &mut State, ctx: &mut Rltk, range: u32, item: Entity) -> ItemMenuResult {
+    let cursor = move_cursor(&gs.ecs, ctx);
+    let camera = Camera::from_ecs(&gs.ecs);
     let player_entity = gs.ecs.fetch::<Entity>();
     let player_pos = gs.ecs.fetch::<Point>();
     let viewsheds = gs.ecs.read_storage::<Viewshed>();
+    let areas_of_effect = gs.ecs.read_storage::<AreaOfEffect>();
+    let map = gs.ecs.fetch::<Map>();
+    let catalogue = gs.ecs.fetch::<Catalogue>();
 
     ctx.print_color(
         5,
         0,
         RGB::named(rltk::YELLOW),
         RGB::named(rltk::BLACK),
-        "Selecione o alvo",
+        catalogue.text("targeting.title"),
     );
 
     // ressaltar células dentro do alcance
@@ -348,7 +955,7 @@ pub fn ranged_target(gs: &mut State, ctx: &mut Rltk, range: u32) -> ItemMenuResu
     if let Some(Viewshed { visible_tiles, .. }) = viewsheds.get(*player_entity) {
         for tile in visible_tiles.iter() {
             if rltk::DistanceAlg::Pythagoras.distance2d(*player_pos, *tile) <= range as f32 {
-                ctx.set_bg(tile.x, tile.y, RGB::named(rltk::BLUE));
+                set_bg_at(ctx, camera, *tile, RGB::named(rltk::BLUE));
                 available_cells.push(tile);
             }
         }
@@ -356,21 +963,39 @@ pub fn ranged_target(gs: &mut State, ctx: &mut Rltk, range: u32) -> ItemMenuResu
         return ItemMenuResult::Cancel;
     }
 
+    let action = gs
+        .ecs
+        .fetch::<KeyMap>()
+        .action(KeyContext::Targeting, ctx.key);
+    if action == Some(Action::Cancel) {
+        return ItemMenuResult::Cancel;
+    }
+
     // desenhar cursor
-    let mouse_pos = ctx.mouse_pos();
-    let valid_target = available_cells
-        .iter()
-        .any(|tile| tile.x == mouse_pos.0 && tile.y == mouse_pos.1);
+    let valid_target = available_cells.iter().any(|tile| **tile == cursor);
     if valid_target {
-        ctx.set_bg(mouse_pos.0, mouse_pos.1, RGB::named(rltk::CYAN));
-        if ctx.left_click {
-            return ItemMenuResult::RangeSelected(Point {
-                x: mouse_pos.0,
-                y: mouse_pos.1,
-            });
+        // mostra a área antes de confirmar, para ninguém se explodir sem querer
+        if let Some(aoe) = areas_of_effect.get(item) {
+            let tiles = area_of_effect::affected_tiles(&map, *player_pos, cursor, aoe);
+            for tile in tiles.iter() {
+                set_bg_at(ctx, camera, *tile, RGB::named(rltk::ORANGE));
+            }
+            if tiles.contains(&*player_pos) {
+                ctx.print_color(
+                    25,
+                    0,
+                    RGB::named(rltk::RED),
+                    RGB::named(rltk::BLACK),
+                    catalogue.text("targeting.self_warning"),
+                );
+            }
+        }
+        set_bg_at(ctx, camera, cursor, RGB::named(rltk::CYAN));
+        if ctx.left_click || action == Some(Action::Confirm) {
+            return ItemMenuResult::RangeSelected(cursor);
         }
     } else {
-        ctx.set_bg(mouse_pos.0, mouse_pos.1, RGB::named(rltk::RED));
+        set_bg_at(ctx, camera, cursor, RGB::named(rltk::RED));
         if ctx.left_click {
             return ItemMenuResult::Cancel;
         }
@@ -379,14 +1004,542 @@ pub fn ranged_target(gs: &mut State, ctx: &mut Rltk, range: u32) -> ItemMenuResu
     ItemMenuResult::NoResponse
 }
 
+/// Pinta o fundo da célula do mundo, se ela estiver na tela.
+fn set_bg_at(ctx: &mut Rltk, camera: Camera, world: Point, color: RGB) {
+    if let Some(screen) = camera.to_screen(world) {
+        ctx.set_bg(screen.x, screen.y, color);
+    }
+}
+
+/// Modo de olhar: o cursor percorre o mapa mostrando o que há em cada célula.
+pub fn look_mode(gs: &mut State, ctx: &mut Rltk) -> MenuResult {
+    let cursor = move_cursor(&gs.ecs, ctx);
+    let camera = Camera::from_ecs(&gs.ecs);
+    let catalogue = gs.ecs.fetch::<Catalogue>();
+
+    ctx.print_color(
+        5,
+        0,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        catalogue.text("look.title"),
+    );
+    set_bg_at(ctx, camera, cursor, RGB::named(rltk::MAGENTA));
+
+    let mut description = describe_tile(&gs.ecs, cursor);
+    if description.is_empty() {
+        let map = gs.ecs.fetch::<Map>();
+        let idx = map.xy_idx(cursor.x as usize, cursor.y as usize);
+        if !map.revealed_tiles[idx] {
+            description.push(catalogue.text("look.unknown"));
+        } else if map.tiles[idx] == TileType::Wall {
+            description.push(catalogue.text("tile.wall"));
+        } else {
+            description.push(catalogue.text("tile.floor"));
+        }
+    }
+    if let Some(screen) = camera.to_screen(cursor) {
+        draw_tooltips_at(ctx, (screen.x, screen.y), description);
+    }
+
+    match gs
+        .ecs
+        .fetch::<KeyMap>()
+        .action(KeyContext::Targeting, ctx.key)
+    {
+        Some(Action::Cancel) | Some(Action::Look) => MenuResult::Cancel,
+        _ => MenuResult::NoResponse,
+    }
+}
+
+/// Escreve a mensagem pedaço por pedaço, cada um na sua cor.
+fn print_log_entry(ctx: &mut Rltk, x: i32, y: i32, entry: &LogEntry, catalogue: &Catalogue) {
+    let bg = RGB::named(rltk::BLACK);
+    let mut x = x;
+    for fragment in entry.fragments(catalogue).iter() {
+        ctx.print_color(x, y, fragment.color, bg, &fragment.text);
+        x += fragment.text.chars().count() as i32;
+    }
+    if entry.repeats > 1 {
+        ctx.print_color(
+            x,
+            y,
+            RGB::named(rltk::GREY),
+            bg,
+            format!(" x{}", entry.repeats),
+        );
+    }
+}
+
+/// Estado do visualizador do registro de mensagens.
+#[derive(Default)]
+pub struct LogViewer {
+    /// Quantas linhas acima da mensagem mais recente a tela está.
+    scroll: usize,
+    filter: Option<LogCategory>,
+    search: String,
+    typing: bool,
+}
+
+const LOG_VIEWER_ROWS: usize = 43;
+
+fn next_filter(filter: Option<LogCategory>) -> Option<LogCategory> {
+    match filter {
+        None => Some(LogCategory::Combat),
+        Some(LogCategory::Combat) => Some(LogCategory::Item),
+        Some(LogCategory::Item) => Some(LogCategory::System),
+        Some(LogCategory::System) => None,
+    }
+}
+
+fn log_entry_matches(
+    entry: &LogEntry,
+    filter: Option<LogCategory>,
+    search: &str,
+    catalogue: &Catalogue,
+) -> bool {
+    filter.map_or(true, |category| entry.category == category)
+        && entry
+            .text(catalogue)
+            .to_lowercase()
+            .contains(&search.to_lowercase())
+}
+
+/// Registro inteiro em tela cheia, com rolagem, filtro por categoria e busca.
+pub fn log_viewer(gs: &mut State, ctx: &mut Rltk) -> MenuResult {
+    let mut viewer = gs.ecs.fetch_mut::<LogViewer>();
+    let mut game_log = gs.ecs.fetch_mut::<GameLog>();
+    let catalogue = gs.ecs.fetch::<Catalogue>();
+    let fg = RGB::named(rltk::WHITE);
+    let bg = RGB::named(rltk::BLACK);
+    let highlight = RGB::named(rltk::YELLOW);
+
+    let shown = game_log
+        .entries
+        .iter()
+        .filter(|entry| log_entry_matches(entry, viewer.filter, &viewer.search, &catalogue))
+        .collect::<Vec<_>>();
+    let max_scroll = shown.len().saturating_sub(LOG_VIEWER_ROWS);
+    viewer.scroll = viewer.scroll.min(max_scroll);
+
+    // a tela inteira é do registro, inclusive por cima dos blocos do mapa
+    ctx.set_active_console(TILE_CONSOLE);
+    ctx.cls();
+    ctx.set_active_console(ASCII_CONSOLE);
+    ctx.cls();
+    ctx.draw_box(0, 0, 79, 49, fg, bg);
+    ctx.print_color(2, 0, highlight, bg, catalogue.text("log_viewer.title"));
+    ctx.print_color(
+        2,
+        1,
+        highlight,
+        bg,
+        catalogue.format(
+            "log_viewer.status",
+            &[
+                (
+                    "filter",
+                    &viewer
+                        .filter
+                        .map_or(catalogue.text("log_viewer.all"), |category| {
+                            catalogue.render(&category.name())
+                        }),
+                ),
+                (
+                    "search",
+                    &format!("{}{}", viewer.search, if viewer.typing { "_" } else { "" }),
+                ),
+            ],
+        ),
+    );
+
+    let end = shown.len() - viewer.scroll;
+    let start = end.saturating_sub(LOG_VIEWER_ROWS);
+    for (i, entry) in shown[start..end].iter().enumerate() {
+        let y = 3 + i as i32;
+        ctx.print_color(
+            2,
+            y,
+            RGB::named(rltk::GREY),
+            bg,
+            format!("{:>5}", entry.turn),
+        );
+        print_log_entry(ctx, 8, y, entry, &catalogue);
+    }
+
+    ctx.print_color(2, 47, highlight, bg, catalogue.text("log_viewer.help"));
+    ctx.print_color(
+        2,
+        48,
+        highlight,
+        bg,
+        catalogue.format(
+            "log_viewer.save_help",
+            &[(
+                "choice",
+                &catalogue.text(if game_log.save_at_end {
+                    "common.yes"
+                } else {
+                    "common.no"
+                }),
+            )],
+        ),
+    );
+
+    if viewer.typing {
+        match ctx.key {
+            Some(VirtualKeyCode::Return) | Some(VirtualKeyCode::Escape) => viewer.typing = false,
+            Some(VirtualKeyCode::Back) => {
+                viewer.search.pop();
+            }
+            Some(VirtualKeyCode::Space) => viewer.search.push(' '),
+            Some(key) => {
+                let letter = rltk::letter_to_option(key);
+                if letter > -1 {
+                    viewer.search.push((b'a' + letter as u8) as char);
+                }
+            }
+            None => {}
+        }
+        return MenuResult::NoResponse;
+    }
+
+    match gs
+        .ecs
+        .fetch::<KeyMap>()
+        .action(KeyContext::LogViewer, ctx.key)
+    {
+        Some(Action::Cancel) => return MenuResult::Cancel,
+        Some(Action::MoveNorth) => viewer.scroll += 1,
+        Some(Action::MoveSouth) => viewer.scroll = viewer.scroll.saturating_sub(1),
+        Some(Action::PageUp) => viewer.scroll += LOG_VIEWER_ROWS,
+        Some(Action::PageDown) => viewer.scroll = viewer.scroll.saturating_sub(LOG_VIEWER_ROWS),
+        Some(Action::ScrollTop) => viewer.scroll = max_scroll,
+        Some(Action::ScrollBottom) => viewer.scroll = 0,
+        Some(Action::Search) => {
+            viewer.search.clear();
+            viewer.typing = true;
+        }
+        Some(Action::ToggleLogSave) => game_log.save_at_end = !game_log.save_at_end,
+        Some(Action::NextTarget) => {
+            viewer.filter = next_filter(viewer.filter);
+            viewer.scroll = 0;
+        }
+        _ => {}
+    }
+    MenuResult::NoResponse
+}
+
+/// Resumo de quem morreu: nome, classe, nível e em que turno. Qualquer
+/// confirmação volta ao menu principal.
+pub fn game_over(gs: &mut State, ctx: &mut Rltk) -> MenuResult {
+    let player_entity = gs.ecs.fetch::<Entity>();
+    let names = gs.ecs.read_storage::<Name>();
+    let classes = gs.ecs.read_storage::<CharacterClass>();
+    let progressions = gs.ecs.read_storage::<Progression>();
+    let turn = gs.ecs.fetch::<GameLog>().turn;
+    let catalogue = gs.ecs.fetch::<Catalogue>();
+
+    let fg = RGB::named(rltk::WHITE);
+    let bg = RGB::named(rltk::BLACK);
+    let highlight = RGB::named(rltk::YELLOW);
+
+    ctx.draw_box(15, 18, 50, 10, fg, bg);
+    ctx.print_color(
+        18,
+        18,
+        RGB::named(rltk::RED),
+        bg,
+        catalogue.text("game_over.title"),
+    );
+    ctx.print_color(18, 28, highlight, bg, catalogue.text("game_over.footer"));
+
+    let name = names
+        .get(*player_entity)
+        .map_or(String::new(), |name| catalogue.render(&name.name));
+    match classes.get(*player_entity) {
+        Some(class) => ctx.print_color(
+            17,
+            20,
+            highlight,
+            bg,
+            format!("{}, {}", name, catalogue.render(&class.name())),
+        ),
+        None => ctx.print_color(17, 20, highlight, bg, name),
+    }
+    if let Some(progression) = progressions.get(*player_entity) {
+        ctx.print(
+            17,
+            22,
+            catalogue.format("game_over.level", &[("level", &progression.level)]),
+        );
+    }
+    ctx.print(
+        17,
+        23,
+        catalogue.format("game_over.turn", &[("turn", &turn)]),
+    );
+
+    match gs.ecs.fetch::<KeyMap>().action(KeyContext::Menu, ctx.key) {
+        Some(Action::Confirm) | Some(Action::Cancel) => MenuResult::Cancel,
+        _ => MenuResult::NoResponse,
+    }
+}
+
+pub fn character_sheet(gs: &mut State, ctx: &mut Rltk) -> MenuResult {
+    let player_entity = gs.ecs.fetch::<Entity>();
+    let names = gs.ecs.read_storage::<Name>();
+    let attributes = gs.ecs.read_storage::<Attributes>();
+    let skills = gs.ecs.read_storage::<Skills>();
+    let progressions = gs.ecs.read_storage::<Progression>();
+    let combat_stats = gs.ecs.read_storage::<CombatStats>();
+    let classes = gs.ecs.read_storage::<CharacterClass>();
+    let catalogue = gs.ecs.fetch::<Catalogue>();
+
+    let (name, attrs, skills, progression, stats) = match (
+        names.get(*player_entity),
+        attributes.get(*player_entity),
+        skills.get(*player_entity),
+        progressions.get(*player_entity),
+        combat_stats.get(*player_entity),
+    ) {
+        (Some(n), Some(a), Some(s), Some(p), Some(c)) => (n, a, s, p, c),
+        _ => return MenuResult::Cancel,
+    };
+
+    let fg = RGB::named(rltk::WHITE);
+    let bg = RGB::named(rltk::BLACK);
+    let highlight = RGB::named(rltk::YELLOW);
+
+    ctx.draw_box(15, 8, 50, 26, fg, bg);
+    ctx.print_color(18, 8, highlight, bg, catalogue.text("sheet.title"));
+    ctx.print_color(18, 34, highlight, bg, catalogue.text("sheet.footer"));
+
+    match classes.get(*player_entity) {
+        Some(class) => ctx.print_color(
+            17,
+            10,
+            highlight,
+            bg,
+            format!(
+                "{}, {}",
+                catalogue.render(&name.name),
+                catalogue.render(&class.name())
+            ),
+        ),
+        None => ctx.print_color(17, 10, highlight, bg, catalogue.render(&name.name)),
+    }
+    ctx.print(
+        17,
+        11,
+        catalogue.format(
+            "sheet.level",
+            &[
+                ("level", &progression.level),
+                ("xp", &progression.xp),
+                ("next", &(progression.level * attributes::XP_PER_LEVEL)),
+            ],
+        ),
+    );
+
+    ctx.print_color(17, 13, highlight, bg, catalogue.text("sheet.attributes"));
+    let attribute_rows = [
+        ("attribute.might", attrs.might),
+        ("attribute.fitness", attrs.fitness),
+        ("attribute.quickness", attrs.quickness),
+        ("attribute.intelligence", attrs.intelligence),
+    ];
+    for (i, (label, value)) in attribute_rows.iter().enumerate() {
+        ctx.print(
+            19,
+            14 + i as i32,
+            format!(
+                "{:<12}{:>3} ({:+})",
+                catalogue.text(label),
+                value,
+                attributes::attribute_bonus(*value)
+            ),
+        );
+    }
+
+    ctx.print_color(17, 19, highlight, bg, catalogue.text("sheet.skills"));
+    let skill_rows = [
+        ("skill.melee", skills.melee),
+        ("skill.defense", skills.defense),
+        ("skill.magic", skills.magic),
+    ];
+    for (i, (label, value)) in skill_rows.iter().enumerate() {
+        ctx.print(
+            19,
+            20 + i as i32,
+            format!("{:<12}{:>+3}", catalogue.text(label), value),
+        );
+    }
+
+    ctx.print_color(17, 24, highlight, bg, catalogue.text("sheet.derived"));
+    ctx.print(
+        19,
+        25,
+        format!(
+            "{:<22}{} / {}",
+            catalogue.text("sheet.health"),
+            stats.hp,
+            stats.max_hp
+        ),
+    );
+    ctx.print(
+        19,
+        26,
+        format!(
+            "{:<22}{}",
+            catalogue.text("sheet.armour_class"),
+            stats.armour_class
+        ),
+    );
+    ctx.print(
+        19,
+        27,
+        format!(
+            "{:<22}{:+}",
+            catalogue.text("sheet.hit_bonus"),
+            attributes::hit_bonus(attrs, skills)
+        ),
+    );
+    ctx.print(
+        19,
+        28,
+        format!(
+            "{:<22}{:+}",
+            catalogue.text("sheet.damage_bonus"),
+            attributes::damage_bonus(attrs)
+        ),
+    );
+    ctx.print(
+        19,
+        29,
+        format!(
+            "{:<22}{:.1} kg",
+            catalogue.text("sheet.carry_capacity"),
+            attributes::carry_capacity(attrs)
+        ),
+    );
+
+    match gs.ecs.fetch::<KeyMap>().action(KeyContext::Menu, ctx.key) {
+        Some(Action::Cancel) => MenuResult::Cancel,
+        _ => MenuResult::NoResponse,
+    }
+}
+
+pub fn character_creation(gs: &mut State, ctx: &mut Rltk) -> CharacterCreationResult {
+    let mut draft = gs.ecs.fetch_mut::<CharacterDraft>();
+    let catalogue = gs.ecs.fetch::<Catalogue>();
+    let fg = RGB::named(rltk::WHITE);
+    let bg = RGB::named(rltk::BLACK);
+    let highlight = RGB::named(rltk::YELLOW);
+
+    ctx.print_color_centered(10, highlight, bg, catalogue.text("creation.title"));
+
+    ctx.print_color(20, 13, highlight, bg, catalogue.text("creation.name"));
+    ctx.print_color(26, 13, fg, bg, format!("{}_", draft.name));
+
+    ctx.print_color(20, 16, highlight, bg, catalogue.text("creation.class"));
+    let classes = [
+        (CharacterClass::Warrior, "creation.warrior"),
+        (CharacterClass::Mage, "creation.mage"),
+        (CharacterClass::Rogue, "creation.rogue"),
+    ];
+    for (i, (class, description)) in classes.iter().enumerate() {
+        let y = 18 + 2 * i as i32;
+        let color = if *class == draft.class {
+            RGB::named(rltk::MAGENTA)
+        } else {
+            fg
+        };
+        ctx.print_color(22, y, color, bg, catalogue.render(&class.name()));
+        ctx.print_color(
+            34,
+            y,
+            RGB::named(rltk::GREY),
+            bg,
+            catalogue.text(description),
+        );
+    }
+
+    ctx.print_color_centered(26, highlight, bg, catalogue.text("creation.help"));
+    ctx.print_color_centered(27, highlight, bg, catalogue.text("creation.footer"));
+
+    let key = match ctx.key {
+        None => return CharacterCreationResult::NoResponse,
+        Some(key) => key,
+    };
+
+    // letras, espaço e apagar escrevem o nome; o resto passa pelo mapa de
+    // teclas
+    let letter = rltk::letter_to_option(key);
+    if letter > -1 {
+        if draft.name.len() < MAX_NAME_LENGTH {
+            let c = (b'a' + letter as u8) as char;
+            let c = if ctx.shift || draft.name.is_empty() {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            };
+            draft.name.push(c);
+        }
+        return CharacterCreationResult::NoResponse;
+    }
+    match key {
+        VirtualKeyCode::Back => {
+            draft.name.pop();
+            return CharacterCreationResult::NoResponse;
+        }
+        VirtualKeyCode::Space => {
+            if !draft.name.is_empty() && draft.name.len() < MAX_NAME_LENGTH {
+                draft.name.push(' ');
+            }
+            return CharacterCreationResult::NoResponse;
+        }
+        _ => {}
+    }
+
+    match gs.ecs.fetch::<KeyMap>().action(KeyContext::Menu, Some(key)) {
+        Some(Action::Cancel) => CharacterCreationResult::Cancel,
+        Some(Action::Confirm) => {
+            if draft.name.trim().is_empty() {
+                CharacterCreationResult::NoResponse
+            } else {
+                CharacterCreationResult::Done
+            }
+        }
+        Some(Action::MoveNorth) => {
+            draft.class = match draft.class {
+                CharacterClass::Warrior => CharacterClass::Rogue,
+                CharacterClass::Mage => CharacterClass::Warrior,
+                CharacterClass::Rogue => CharacterClass::Mage,
+            };
+            CharacterCreationResult::NoResponse
+        }
+        Some(Action::MoveSouth) => {
+            draft.class = match draft.class {
+                CharacterClass::Warrior => CharacterClass::Mage,
+                CharacterClass::Mage => CharacterClass::Rogue,
+                CharacterClass::Rogue => CharacterClass::Warrior,
+            };
+            CharacterCreationResult::NoResponse
+        }
+        _ => CharacterCreationResult::NoResponse,
+    }
+}
+
 pub fn main_menu(gs: &mut State, ctx: &mut Rltk) -> MainMenuResult {
     let runstate = gs.ecs.fetch::<RunState>();
+    let catalogue = gs.ecs.fetch::<Catalogue>();
 
     ctx.print_color_centered(
         15,
         RGB::named(rltk::YELLOW),
         RGB::named(rltk::BLACK),
-        "Tutorial do Rufião",
+        catalogue.text("menu.main.title"),
     );
 
     match *runstate {
@@ -395,27 +1548,40 @@ pub fn main_menu(gs: &mut State, ctx: &mut Rltk) -> MainMenuResult {
                 24,
                 color_for(MainMenuSelection::NewGame, selected),
                 RGB::named(rltk::BLACK),
-                "Novo Jogo",
+                catalogue.text("menu.main.new_game"),
             );
             ctx.print_color_centered(
                 25,
                 color_for(MainMenuSelection::Load, selected),
                 RGB::named(rltk::BLACK),
-                "Carregar",
+                catalogue.text("menu.main.load"),
             );
             ctx.print_color_centered(
                 26,
+                color_for(MainMenuSelection::Language, selected),
+                RGB::named(rltk::BLACK),
+                catalogue.format(
+                    "menu.main.language",
+                    &[("language", &catalogue.text("language.name"))],
+                ),
+            );
+            ctx.print_color_centered(
+                27,
+                color_for(MainMenuSelection::Settings, selected),
+                RGB::named(rltk::BLACK),
+                catalogue.text("menu.main.settings"),
+            );
+            ctx.print_color_centered(
+                28,
                 color_for(MainMenuSelection::Quit, selected),
                 RGB::named(rltk::BLACK),
-                "Sair",
+                catalogue.text("menu.main.quit"),
             );
 
-            match ctx.key {
-                None => MainMenuResult::NoSelection(selected),
-                Some(VirtualKeyCode::Escape) => MainMenuResult::NoSelection(selected),
-                Some(VirtualKeyCode::Up) => MainMenuResult::NoSelection(previous_option(selected)),
-                Some(VirtualKeyCode::Down) => MainMenuResult::NoSelection(next_option(selected)),
-                Some(VirtualKeyCode::Return) => MainMenuResult::Selected(selected),
+            match gs.ecs.fetch::<KeyMap>().action(KeyContext::Menu, ctx.key) {
+                Some(Action::MoveNorth) => MainMenuResult::NoSelection(previous_option(selected)),
+                Some(Action::MoveSouth) => MainMenuResult::NoSelection(next_option(selected)),
+                Some(Action::Confirm) => MainMenuResult::Selected(selected),
                 _ => MainMenuResult::NoSelection(selected),
             }
         }
@@ -435,14 +1601,115 @@ fn previous_option(selected: MainMenuSelection) -> MainMenuSelection {
     match selected {
         MainMenuSelection::NewGame => MainMenuSelection::Quit,
         MainMenuSelection::Load => MainMenuSelection::NewGame,
-        MainMenuSelection::Quit => MainMenuSelection::Load,
+        MainMenuSelection::Language => MainMenuSelection::Load,
+        MainMenuSelection::Settings => MainMenuSelection::Language,
+        MainMenuSelection::Quit => MainMenuSelection::Settings,
     }
 }
 
 fn next_option(selected: MainMenuSelection) -> MainMenuSelection {
     match selected {
         MainMenuSelection::NewGame => MainMenuSelection::Load,
-        MainMenuSelection::Load => MainMenuSelection::Quit,
+        MainMenuSelection::Load => MainMenuSelection::Language,
+        MainMenuSelection::Language => MainMenuSelection::Settings,
+        MainMenuSelection::Settings => MainMenuSelection::Quit,
         MainMenuSelection::Quit => MainMenuSelection::NewGame,
     }
 }
+
+/// Configurações: por enquanto só o modo de exibição, que ENTER alterna.
+pub fn settings_menu(gs: &mut State, ctx: &mut Rltk) -> MenuResult {
+    let catalogue = gs.ecs.fetch::<Catalogue>();
+    ctx.print_color_centered(
+        15,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        catalogue.text("settings.title"),
+    );
+    let mode = *gs.ecs.fetch::<RenderMode>();
+    ctx.print_color_centered(
+        24,
+        RGB::named(rltk::MAGENTA),
+        RGB::named(rltk::BLACK),
+        catalogue.format(
+            "settings.render",
+            &[("mode", &catalogue.render(&mode.name()))],
+        ),
+    );
+    ctx.print_color_centered(
+        26,
+        RGB::named(rltk::GREY),
+        RGB::named(rltk::BLACK),
+        catalogue.text("settings.help"),
+    );
+
+    match gs.ecs.fetch::<KeyMap>().action(KeyContext::Menu, ctx.key) {
+        Some(Action::Cancel) => return MenuResult::Cancel,
+        Some(Action::Confirm) => {}
+        _ => return MenuResult::NoResponse,
+    }
+    *gs.ecs.write_resource::<RenderMode>() = mode.next();
+    MenuResult::NoResponse
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Language;
+
+    fn hit(category: LogCategory) -> LogEntry {
+        let mut game_log = GameLog::new();
+        game_log
+            .entry(category, "melee.miss")
+            .arg("attacker", "Goblin")
+            .arg("target", "Fulano")
+            .log();
+        game_log.entries.pop().unwrap()
+    }
+
+    #[test]
+    fn filters_cycle_through_every_category() {
+        let mut filter = None;
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            filter = next_filter(filter);
+            seen.push(filter);
+        }
+        assert_eq!(
+            seen,
+            vec![
+                Some(LogCategory::Combat),
+                Some(LogCategory::Item),
+                Some(LogCategory::System),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn entries_match_the_filter_and_the_search() {
+        let catalogue = Catalogue::load(Language::Portuguese).0;
+        let hit = hit(LogCategory::Combat);
+        assert!(log_entry_matches(&hit, None, "", &catalogue));
+        assert!(log_entry_matches(
+            &hit,
+            Some(LogCategory::Combat),
+            "",
+            &catalogue
+        ));
+        assert!(!log_entry_matches(
+            &hit,
+            Some(LogCategory::Item),
+            "",
+            &catalogue
+        ));
+        assert!(log_entry_matches(&hit, None, "goblin", &catalogue));
+        assert!(log_entry_matches(
+            &hit,
+            Some(LogCategory::Combat),
+            "FULANO",
+            &catalogue
+        ));
+        assert!(!log_entry_matches(&hit, None, "orc", &catalogue));
+    }
+}
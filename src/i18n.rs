@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+
+/// Pasta com os catálogos de mensagens, um arquivo por idioma.
+pub const LANG_DIR: &str = "lang";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Language {
+    Portuguese,
+    English,
+}
+
+impl Language {
+    /// Código usado no nome do arquivo do catálogo.
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::Portuguese => "pt",
+            Language::English => "en",
+        }
+    }
+
+    pub fn next(self) -> Language {
+        match self {
+            Language::Portuguese => Language::English,
+            Language::English => Language::Portuguese,
+        }
+    }
+
+    /// Catálogo embutido no executável, usado quando falta o arquivo.
+    fn builtin(self) -> &'static str {
+        match self {
+            Language::Portuguese => include_str!("../lang/pt.txt"),
+            Language::English => include_str!("../lang/en.txt"),
+        }
+    }
+}
+
+/// Mensagens de um idioma, indexadas por ID. O que faltar no idioma escolhido
+/// sai em português e, em último caso, como o próprio ID. Fica no `World`
+/// como recurso; trocar de idioma é trocar o recurso.
+pub struct Catalogue {
+    language: Language,
+    messages: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+/// Lê as linhas `id = texto` de um catálogo; linhas começando com `#` são
+/// comentários.
+fn parse_bundle(text: &str) -> Result<HashMap<String, String>, String> {
+    let mut messages = HashMap::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (id, message) = line
+            .split_once('=')
+            .ok_or(format!("linha {}: esperava `id = texto`", n + 1))?;
+        messages.insert(id.trim().to_string(), message.trim().to_string());
+    }
+    Ok(messages)
+}
+
+impl Catalogue {
+    /// Lê `LANG_DIR/<código>.txt`; sem o arquivo, ou com ele quebrado, vale
+    /// o catálogo embutido e o erro é devolvido junto.
+    pub fn load(language: Language) -> (Self, Option<String>) {
+        let path = format!("{}/{}.txt", LANG_DIR, language.code());
+        let (messages, error) = match fs::read_to_string(&path) {
+            Ok(text) => match parse_bundle(&text) {
+                Ok(messages) => (messages, None),
+                Err(error) => (
+                    parse_bundle(language.builtin()).unwrap_or_default(),
+                    Some(format!("{}: {}", path, error)),
+                ),
+            },
+            Err(_) => (parse_bundle(language.builtin()).unwrap_or_default(), None),
+        };
+        let fallback = parse_bundle(Language::Portuguese.builtin()).unwrap_or_default();
+        (
+            Self {
+                language,
+                messages,
+                fallback,
+            },
+            error,
+        )
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    pub fn text(&self, id: &str) -> String {
+        self.messages
+            .get(id)
+            .or_else(|| self.fallback.get(id))
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    /// A mensagem `id` com cada `{marcador}` trocado pelo valor correspondente.
+    pub fn format(&self, id: &str, args: &[(&str, &dyn Display)]) -> String {
+        fill(&self.text(id), |key| {
+            args.iter()
+                .find(|(k, _value)| *k == key)
+                .map(|(_key, value)| value.to_string())
+        })
+    }
+
+    /// O texto no idioma deste catálogo.
+    pub fn render(&self, text: &Text) -> String {
+        match text {
+            Text::Literal(literal) => literal.clone(),
+            Text::Message(id, args) => fill(&self.text(id), |key| {
+                args.iter()
+                    .find(|(k, _value)| *k == key)
+                    .map(|(_key, value)| self.render(value))
+            }),
+        }
+    }
+}
+
+/// Texto para o jogador guardado sem traduzir: uma mensagem do catálogo com
+/// os valores dos seus `{marcadores}`, ou um texto pronto, como o nome que o
+/// jogador digitou. Só vira `String` na hora de aparecer, pelo `Catalogue`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Text {
+    Message(&'static str, Vec<(&'static str, Text)>),
+    Literal(String),
+}
+
+impl Text {
+    pub fn message(id: &'static str) -> Text {
+        Text::Message(id, Vec::new())
+    }
+
+    /// Valor do `{marcador}` `key`; num texto pronto não muda nada.
+    pub fn arg<T: ToText>(self, key: &'static str, value: T) -> Text {
+        match self {
+            Text::Message(id, mut args) => {
+                args.push((key, value.to_text()));
+                Text::Message(id, args)
+            }
+            literal => literal,
+        }
+    }
+}
+
+/// O que pode preencher um `{marcador}`: números e textos prontos entram
+/// como estão, `Text` é traduzido junto com a mensagem.
+pub trait ToText {
+    fn to_text(&self) -> Text;
+}
+
+impl<T: Display + ?Sized> ToText for T {
+    fn to_text(&self) -> Text {
+        Text::Literal(self.to_string())
+    }
+}
+
+impl ToText for Text {
+    fn to_text(&self) -> Text {
+        self.clone()
+    }
+}
+
+impl ToText for &Text {
+    fn to_text(&self) -> Text {
+        (*self).clone()
+    }
+}
+
+/// Pedaço de uma mensagem: texto fixo ou o nome de um `{marcador}`.
+#[derive(PartialEq, Debug)]
+pub enum Piece<'a> {
+    Literal(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Quebra a mensagem em texto fixo e marcadores `{nome}`.
+pub fn pieces(template: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let close = match rest[open..].find('}') {
+            Some(close) => open + close,
+            None => break,
+        };
+        if open > 0 {
+            pieces.push(Piece::Literal(&rest[..open]));
+        }
+        pieces.push(Piece::Placeholder(&rest[open + 1..close]));
+        rest = &rest[close + 1..];
+    }
+    if !rest.is_empty() {
+        pieces.push(Piece::Literal(rest));
+    }
+    pieces
+}
+
+/// Troca cada `{marcador}` de `template` pelo que `value` der; marcador sem
+/// valor fica como está.
+fn fill(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    pieces(template)
+        .iter()
+        .map(|piece| match piece {
+            Piece::Literal(literal) => literal.to_string(),
+            Piece::Placeholder(key) => value(key).unwrap_or_else(|| format!("{{{}}}", key)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(template: &str) -> Vec<&str> {
+        let mut keys = pieces(template)
+            .into_iter()
+            .filter_map(|piece| match piece {
+                Piece::Placeholder(key) => Some(key),
+                Piece::Literal(_) => None,
+            })
+            .collect::<Vec<_>>();
+        keys.sort_unstable();
+        keys
+    }
+
+    #[test]
+    fn splits_placeholders() {
+        assert_eq!(
+            pieces("{a} acerta {b}!"),
+            vec![
+                Piece::Placeholder("a"),
+                Piece::Literal(" acerta "),
+                Piece::Placeholder("b"),
+                Piece::Literal("!"),
+            ]
+        );
+    }
+
+    #[test]
+    fn bundles_have_the_same_messages() {
+        let pt = parse_bundle(Language::Portuguese.builtin()).unwrap();
+        let en = parse_bundle(Language::English.builtin()).unwrap();
+        for (id, message) in pt.iter() {
+            let translation = en
+                .get(id)
+                .unwrap_or_else(|| panic!("falta `{}` em inglês", id));
+            assert_eq!(placeholders(message), placeholders(translation), "{}", id);
+        }
+        assert_eq!(pt.len(), en.len());
+    }
+
+    #[test]
+    fn the_same_text_follows_the_catalogue() {
+        let look = Text::message("appearance.potion")
+            .arg("look", Text::message("appearance.potion.golden"));
+        let pt = Catalogue::load(Language::Portuguese).0;
+        let en = Catalogue::load(Language::English).0;
+        assert_eq!(pt.render(&look), "Poção Dourada");
+        assert_eq!(en.render(&look), "Golden Potion");
+        assert_eq!(en.render(&Text::Literal("Fulano".to_string())), "Fulano");
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use rltk::RandomNumberGenerator;
+
+use crate::components::ItemKind;
+use crate::components::Name;
+use crate::components::ObfuscatedName;
+use crate::i18n::Text;
+
+const SYLLABLES: [&str; 12] = [
+    "XY", "ZZY", "KLA", "TU", "FOO", "BAR", "NI", "GRA", "MOR", "QUA", "ZE", "LU",
+];
+/// IDs no catálogo de mensagens.
+const POTION_LOOKS: [&str; 10] = [
+    "appearance.potion.turbid",
+    "appearance.potion.bubbling",
+    "appearance.potion.smoking",
+    "appearance.potion.thick",
+    "appearance.potion.sparkling",
+    "appearance.potion.viscous",
+    "appearance.potion.milky",
+    "appearance.potion.golden",
+    "appearance.potion.black",
+    "appearance.potion.fizzy",
+];
+const WAND_MATERIALS: [&str; 8] = [
+    "appearance.wand.oak",
+    "appearance.wand.bone",
+    "appearance.wand.crystal",
+    "appearance.wand.iron",
+    "appearance.wand.ebony",
+    "appearance.wand.ivory",
+    "appearance.wand.copper",
+    "appearance.wand.glass",
+];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ItemCategory {
+    Scroll,
+    Potion,
+    Wand,
+}
+
+/// O que o jogador sabe sobre os itens mágicos desta partida: a aparência
+/// sorteada para cada tipo de item e quais tipos já foram identificados.
+#[derive(Default)]
+pub struct ItemKnowledge {
+    appearances: HashMap<&'static str, Text>,
+    identified: HashSet<&'static str>,
+}
+
+impl ItemKnowledge {
+    /// Aparência do tipo de item `kind`, sorteada na primeira vez em que ele
+    /// aparece e repetida em todos os itens do mesmo tipo.
+    pub fn appearance(
+        &mut self,
+        rng: &mut RandomNumberGenerator,
+        kind: ItemKind,
+        category: ItemCategory,
+    ) -> Text {
+        if let Some(appearance) = self.appearances.get(kind.id) {
+            return appearance.clone();
+        }
+
+        let mut appearance = random_appearance(rng, category);
+        let mut attempts = 0;
+        while self.appearances.values().any(|a| *a == appearance) {
+            attempts += 1;
+            appearance = if attempts < 100 {
+                random_appearance(rng, category)
+            } else {
+                Text::message("appearance.numbered")
+                    .arg("appearance", random_appearance(rng, category))
+                    .arg("number", attempts)
+            };
+        }
+
+        self.appearances.insert(kind.id, appearance.clone());
+        appearance
+    }
+
+    pub fn is_identified(&self, kind: ItemKind) -> bool {
+        self.identified.contains(kind.id)
+    }
+
+    /// Marca o tipo como identificado; devolve `true` se ainda não era.
+    pub fn identify(&mut self, kind: ItemKind) -> bool {
+        self.identified.insert(kind.id)
+    }
+
+    /// Esquece a identificação do tipo; devolve `true` se ele era conhecido.
+    pub fn forget(&mut self, kind: ItemKind) -> bool {
+        self.identified.remove(kind.id)
+    }
+
+    /// Nome que o jogador enxerga para um item.
+    pub fn name_of(
+        &self,
+        name: &Name,
+        kind: Option<&ItemKind>,
+        obfuscated: Option<&ObfuscatedName>,
+    ) -> Text {
+        match (kind, obfuscated) {
+            (Some(kind), Some(obfuscated)) if !self.is_identified(*kind) => obfuscated.name.clone(),
+            _ => name.name.clone(),
+        }
+    }
+}
+
+fn random_appearance(rng: &mut RandomNumberGenerator, category: ItemCategory) -> Text {
+    match category {
+        ItemCategory::Scroll => {
+            let label = (0..rng.range(2, 4))
+                .map(|_| *rng.random_slice_entry(&SYLLABLES).unwrap())
+                .collect::<String>();
+            Text::message("appearance.scroll").arg("label", label)
+        }
+        ItemCategory::Potion => Text::message("appearance.potion").arg(
+            "look",
+            Text::message(rng.random_slice_entry(&POTION_LOOKS).unwrap()),
+        ),
+        ItemCategory::Wand => Text::message("appearance.wand").arg(
+            "material",
+            Text::message(rng.random_slice_entry(&WAND_MATERIALS).unwrap()),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POTION: ItemKind = ItemKind {
+        id: "item.health_potion",
+    };
+    const SCROLL: ItemKind = ItemKind {
+        id: "item.fireball_scroll",
+    };
+
+    #[test]
+    fn same_kind_keeps_its_appearance() {
+        let mut rng = RandomNumberGenerator::seeded(42);
+        let mut knowledge = ItemKnowledge::default();
+        let first = knowledge.appearance(&mut rng, POTION, ItemCategory::Potion);
+        let second = knowledge.appearance(&mut rng, POTION, ItemCategory::Potion);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn different_kinds_never_share_an_appearance() {
+        let mut rng = RandomNumberGenerator::seeded(7);
+        let mut knowledge = ItemKnowledge::default();
+        let kinds = (0..POTION_LOOKS.len() + 3)
+            .map(|i| {
+                let id = Box::leak(format!("item.potion_{}", i).into_boxed_str());
+                knowledge.appearance(&mut rng, ItemKind { id }, ItemCategory::Potion)
+            })
+            .collect::<HashSet<_>>();
+        assert_eq!(kinds.len(), POTION_LOOKS.len() + 3);
+    }
+
+    fn scroll_names() -> (Name, ObfuscatedName) {
+        (
+            Name {
+                name: Text::message("item.fireball_scroll"),
+            },
+            ObfuscatedName {
+                name: Text::message("appearance.scroll").arg("label", "FOOBAR"),
+            },
+        )
+    }
+
+    #[test]
+    fn identified_items_show_their_real_name() {
+        let mut knowledge = ItemKnowledge::default();
+        let (name, obfuscated) = scroll_names();
+        assert_eq!(
+            knowledge.name_of(&name, Some(&SCROLL), Some(&obfuscated)),
+            obfuscated.name
+        );
+        assert!(knowledge.identify(SCROLL));
+        assert!(!knowledge.identify(SCROLL));
+        assert_eq!(
+            knowledge.name_of(&name, Some(&SCROLL), Some(&obfuscated)),
+            name.name
+        );
+    }
+
+    #[test]
+    fn identification_follows_the_kind() {
+        let mut knowledge = ItemKnowledge::default();
+        knowledge.identify(SCROLL);
+        let (name, obfuscated) = scroll_names();
+        assert_eq!(
+            knowledge.name_of(&name, Some(&POTION), Some(&obfuscated)),
+            obfuscated.name
+        );
+    }
+}
@@ -1,16 +1,42 @@
+use rltk::DiceType;
+use rltk::LineAlg;
+use rltk::Point;
+use rltk::RandomNumberGenerator;
+use rltk::RGB;
 use specs::prelude::*;
 
+use crate::area_of_effect;
+use crate::attributes;
 use crate::components::*;
 use crate::game_log::GameLog;
+use crate::game_log::LogCategory;
+use crate::i18n::Text;
+use crate::identification::ItemKnowledge;
 use crate::map::Map;
+use crate::map::TileType;
+use crate::melee_combat_system::attack_outcome;
+use crate::melee_combat_system::AttackOutcome;
+use crate::particle_system::ParticleBuilder;
+
+/// Alcance de um item arremessado com a mão.
+pub const THROW_RANGE: u32 = 6;
 
 pub struct ItemCollectionSystem {}
 
 impl<'a> System<'a> for ItemCollectionSystem {
     type SystemData = (
+        Entities<'a>,
         ReadExpect<'a, Entity>,
+        ReadExpect<'a, ItemKnowledge>,
         WriteExpect<'a, GameLog>,
         ReadStorage<'a, Name>,
+        ReadStorage<'a, ItemKind>,
+        ReadStorage<'a, ObfuscatedName>,
+        ReadStorage<'a, Cursed>,
+        ReadStorage<'a, Weight>,
+        ReadStorage<'a, Burden>,
+        ReadStorage<'a, Renderable>,
+        WriteStorage<'a, Stackable>,
         WriteStorage<'a, WantsToPickupItem>,
         WriteStorage<'a, Position>,
         WriteStorage<'a, InBackpack>,
@@ -18,28 +44,80 @@ impl<'a> System<'a> for ItemCollectionSystem {
 
     fn run(&mut self, data: Self::SystemData) {
         let (
+            entities,
             player_entity,
+            knowledge,
             mut game_log,
             names,
+            kinds,
+            obfuscated_names,
+            curses,
+            weights,
+            burdens,
+            renderables,
+            mut stackables,
             mut wants_to_pickup_items,
             mut positions,
             mut in_backpacks,
         ) = data;
 
         for pickup in (&wants_to_pickup_items).join() {
-            positions.remove(pickup.item);
-            in_backpacks
-                .insert(
-                    pickup.item,
-                    InBackpack {
-                        owner: pickup.collected_by,
-                    },
-                )
-                .expect("não consegui colocar na bolsa!");
+            let name = knowledge.name_of(
+                names.get(pickup.item).unwrap(),
+                kinds.get(pickup.item),
+                obfuscated_names.get(pickup.item),
+            );
+
+            let quantity = stackables.get(pickup.item).map_or(1, |s| s.quantity);
+            let weight = weights.get(pickup.item).map_or(0.0, |w| w.kg) * quantity as f32;
+            if let Some(burden) = burdens.get(pickup.collected_by) {
+                if !attributes::can_carry(burden.load, weight, burden.capacity) {
+                    if pickup.collected_by == *player_entity {
+                        game_log
+                            .entry(LogCategory::Item, "pickup.too_heavy")
+                            .name("item", &name, renderables.get(pickup.item))
+                            .log();
+                    }
+                    continue;
+                }
+            }
 
+            positions.remove(pickup.item);
             if pickup.collected_by == *player_entity {
-                let name = &names.get(pickup.item).unwrap().name;
-                game_log.entries.push(format!("Você pegou {}", name));
+                game_log
+                    .entry(LogCategory::Item, "pickup.done")
+                    .name("item", &name, renderables.get(pickup.item))
+                    .log();
+            }
+
+            // itens iguais (inclusive na maldição) vão para a mesma pilha
+            let stack = stackables.get(pickup.item).and_then(|_| {
+                let kind = kinds.get(pickup.item);
+                let cursed = curses.get(pickup.item).is_some();
+                (&entities, &in_backpacks, &kinds, &stackables)
+                    .join()
+                    .find(|(item, backpack, stack_kind, _stack)| {
+                        backpack.owner == pickup.collected_by
+                            && Some(*stack_kind) == kind
+                            && curses.get(*item).is_some() == cursed
+                    })
+                    .map(|(item, ..)| item)
+            });
+
+            if let Some(stack) = stack {
+                stackables.get_mut(stack).unwrap().quantity += quantity;
+                entities
+                    .delete(pickup.item)
+                    .expect("não consegui empilhar o item!");
+            } else {
+                in_backpacks
+                    .insert(
+                        pickup.item,
+                        InBackpack {
+                            owner: pickup.collected_by,
+                        },
+                    )
+                    .expect("não consegui colocar na bolsa!");
             }
         }
 
@@ -53,13 +131,33 @@ impl<'a> System<'a> for ItemUseSystem {
     type SystemData = (
         Entities<'a>,
         ReadExpect<'a, Entity>,
-        ReadExpect<'a, Map>,
+        // agrupados porque uma tupla de SystemData aceita no máximo 26 itens
+        (
+            ReadExpect<'a, Map>,
+            ReadStorage<'a, Position>,
+            ReadStorage<'a, Renderable>,
+            ReadStorage<'a, ItemKind>,
+            WriteExpect<'a, RandomNumberGenerator>,
+        ),
         WriteExpect<'a, GameLog>,
+        WriteExpect<'a, ItemKnowledge>,
+        WriteExpect<'a, ParticleBuilder>,
         ReadStorage<'a, Name>,
+        ReadStorage<'a, ObfuscatedName>,
+        ReadStorage<'a, Identifies>,
+        ReadStorage<'a, RemovesCurse>,
+        WriteStorage<'a, Cursed>,
         ReadStorage<'a, ProvidesHealing>,
         ReadStorage<'a, Consumable>,
         ReadStorage<'a, InflictsDamage>,
         ReadStorage<'a, AreaOfEffect>,
+        ReadStorage<'a, Companion>,
+        ReadStorage<'a, Spell>,
+        ReadStorage<'a, Recharges>,
+        ReadStorage<'a, InBackpack>,
+        WriteStorage<'a, Stackable>,
+        WriteStorage<'a, Charges>,
+        WriteStorage<'a, Mana>,
         WriteStorage<'a, Confusion>,
         WriteStorage<'a, CombatStats>,
         WriteStorage<'a, WantsToUseItem>,
@@ -70,13 +168,26 @@ impl<'a> System<'a> for ItemUseSystem {
         let (
             entities,
             player_entity,
-            map,
+            (map, positions, renderables, kinds, mut rng),
             mut game_log,
+            mut knowledge,
+            mut particle_builder,
             names,
+            obfuscated_names,
+            identifiers,
+            curse_removers,
+            mut curses,
             healing_providers,
             consumables,
             inflicts_damages,
             areas_of_effects,
+            companions,
+            spells,
+            rechargers,
+            in_backpacks,
+            mut stackables,
+            mut charges,
+            mut manas,
             mut confusions,
             mut combat_stats,
             mut wants_to_use_items,
@@ -84,28 +195,90 @@ impl<'a> System<'a> for ItemUseSystem {
         ) = data;
 
         for (entity, item_user) in (&entities, &wants_to_use_items).join() {
-            let item_name = names.get(item_user.item).unwrap();
+            let item_name = knowledge.name_of(
+                names.get(item_user.item).unwrap(),
+                kinds.get(item_user.item),
+                obfuscated_names.get(item_user.item),
+            );
+            let item_renderable = renderables.get(item_user.item);
+
+            if let Some(Charges { current: 0, .. }) = charges.get(item_user.item) {
+                if entity == *player_entity {
+                    game_log
+                        .entry(LogCategory::Item, "use.no_charges")
+                        .name("item", &item_name, item_renderable)
+                        .log();
+                }
+                continue;
+            }
+
+            // magias não são consumidas, mas gastam mana
+            let verb = if let Some(spell) = spells.get(item_user.item) {
+                match manas.get_mut(entity) {
+                    Some(mana) if mana.current >= spell.mana_cost => {
+                        mana.current -= spell.mana_cost;
+                    }
+                    _ => {
+                        if entity == *player_entity {
+                            game_log
+                                .entry(LogCategory::Item, "use.no_mana")
+                                .name("spell", &item_name, item_renderable)
+                                .log();
+                        }
+                        continue;
+                    }
+                }
+                Text::message("use.verb.cast")
+            } else {
+                Text::message("use.verb.use")
+            };
 
             let mut targets = Vec::new();
             if let Some(target_point) = item_user.target {
                 let idx = map.xy_idx(target_point.x as usize, target_point.y as usize);
                 if let Some(aoe) = areas_of_effects.get(item_user.item) {
-                    let mut blast_tiles =
-                        rltk::field_of_view(target_point, aoe.radius as i32, &*map);
-                    blast_tiles.retain(|p| {
-                        p.x > 0
-                            && p.x < map.width as i32 - 1
-                            && p.y > 0
-                            && p.y < map.height as i32 - 1
-                    });
+                    let origin = positions
+                        .get(entity)
+                        .map_or(target_point, |pos| Point::new(pos.x, pos.y));
+                    let blast_tiles =
+                        area_of_effect::affected_tiles(&map, origin, target_point, aoe);
                     for blast_tile in blast_tiles.iter() {
+                        particle_builder.request(
+                            blast_tile.x,
+                            blast_tile.y,
+                            RGB::named(rltk::ORANGE),
+                            RGB::named(rltk::BLACK),
+                            rltk::to_cp437('░'),
+                            200.0,
+                        );
                         let blast_tile_idx =
                             map.xy_idx(blast_tile.x as usize, blast_tile.y as usize);
                         for mob in map.tile_content[blast_tile_idx].iter() {
+                            // companheiros do jogador saem da frente da explosão
+                            if entity == *player_entity && companions.get(*mob).is_some() {
+                                if let Some(companion_name) = names.get(*mob) {
+                                    game_log
+                                        .entry(LogCategory::Item, "use.companion_dodges")
+                                        .name(
+                                            "companion",
+                                            &companion_name.name,
+                                            renderables.get(*mob),
+                                        )
+                                        .name("item", &item_name, item_renderable)
+                                        .log();
+                                }
+                                continue;
+                            }
                             targets.push(*mob);
                         }
                     }
                 } else {
+                    particle_builder.projectile(
+                        entity,
+                        target_point,
+                        RGB::named(rltk::CYAN),
+                        rltk::to_cp437('*'),
+                    );
                     for mob in map.tile_content[idx].iter() {
                         targets.push(*mob);
                     }
@@ -114,16 +287,57 @@ impl<'a> System<'a> for ItemUseSystem {
                 targets.push(*player_entity);
             }
 
+            // itens amaldiçoados se voltam contra quem os usa
+            let cursed = if let Some(curse) = curses.get_mut(item_user.item) {
+                curse.known = true;
+                targets = vec![entity];
+                if entity == *player_entity {
+                    game_log
+                        .entry(LogCategory::Item, "use.cursed_backfire")
+                        .name("item", &item_name, item_renderable)
+                        .log();
+                }
+                true
+            } else {
+                false
+            };
+
             if let Some(healer) = healing_providers.get(item_user.item) {
                 for target in targets.iter() {
-                    if let Some(stats) = combat_stats.get_mut(*target) {
+                    if cursed {
+                        SufferDamage::new_damage(
+                            &mut suffer_damages,
+                            *target,
+                            healer.heal_amount as u32,
+                            false,
+                        );
+                        if entity == *player_entity {
+                            game_log
+                                .entry(LogCategory::Item, "use.cursed_healing")
+                                .name("item", &item_name, item_renderable)
+                                .damage("damage", healer.heal_amount)
+                                .log();
+                        }
+                    } else if let Some(stats) = combat_stats.get_mut(*target) {
+                        particle_builder.request_on(
+                            *target,
+                            RGB::named(rltk::GREEN),
+                            rltk::to_cp437('♥'),
+                            200.0,
+                        );
                         stats.hp =
                             i32::min(stats.max_hp as i32, stats.hp + healer.heal_amount as i32);
                         if entity == *player_entity {
-                            game_log.entries.push(format!(
-                                "Você toma uma talagada de {}, e cura {} hp.",
-                                item_name.name, healer.heal_amount
-                            ));
+                            let id = if spells.get(item_user.item).is_some() {
+                                "use.healing_spell"
+                            } else {
+                                "use.healing_potion"
+                            };
+                            game_log
+                                .entry(LogCategory::Item, id)
+                                .name("item", &item_name, item_renderable)
+                                .healing("amount", healer.heal_amount)
+                                .log();
                         }
                     }
                 }
@@ -131,13 +345,27 @@ impl<'a> System<'a> for ItemUseSystem {
 
             if let Some(InflictsDamage { damage }) = inflicts_damages.get(item_user.item) {
                 for target in targets.iter() {
-                    SufferDamage::new_damage(&mut suffer_damages, *target, *damage);
+                    SufferDamage::new_damage(
+                        &mut suffer_damages,
+                        *target,
+                        *damage,
+                        entity == *player_entity && !cursed,
+                    );
+                    particle_builder.request_on(
+                        *target,
+                        RGB::named(rltk::RED),
+                        rltk::to_cp437('‼'),
+                        200.0,
+                    );
                     if entity == *player_entity {
                         let target_name = names.get(*target).unwrap();
-                        game_log.entries.push(format!(
-                            "Você usa {} em {}, causando {} de dano.",
-                            item_name.name, target_name.name, damage
-                        ));
+                        game_log
+                            .entry(LogCategory::Item, "use.damage")
+                            .arg("verb", &verb)
+                            .name("item", &item_name, item_renderable)
+                            .name("target", &target_name.name, renderables.get(*target))
+                            .damage("damage", damage)
+                            .log();
                     }
                 }
             }
@@ -146,12 +374,20 @@ impl<'a> System<'a> for ItemUseSystem {
             if let Some(confusion) = confusions.get(item_user.item) {
                 for target in targets.iter() {
                     to_confuse.push((target, confusion.turns));
+                    particle_builder.request_on(
+                        *target,
+                        RGB::named(rltk::MAGENTA),
+                        rltk::to_cp437('?'),
+                        200.0,
+                    );
                     if entity == *player_entity {
                         let target_name = names.get(*target).unwrap();
-                        game_log.entries.push(format!(
-                            "Você usa {} em {}, causando a maior confusão que até Deus duvida",
-                            item_name.name, target_name.name
-                        ));
+                        game_log
+                            .entry(LogCategory::Item, "use.confusion")
+                            .arg("verb", &verb)
+                            .name("item", &item_name, item_renderable)
+                            .name("target", &target_name.name, renderables.get(*target))
+                            .log();
                     }
                 }
             }
@@ -161,10 +397,152 @@ impl<'a> System<'a> for ItemUseSystem {
                     .expect("não consegui confundir!");
             });
 
-            if let Some(_) = consumables.get(item_user.item) {
-                entities
-                    .delete(item_user.item)
-                    .expect("não consegui consumir!");
+            // amaldiçoado, o pergaminho drena as varinhas em vez de carregá-las
+            if rechargers.get(item_user.item).is_some() {
+                for (wand, backpack) in (&entities, &in_backpacks).join() {
+                    if backpack.owner == entity {
+                        if let Some(wand_charges) = charges.get_mut(wand) {
+                            wand_charges.current = if cursed { 0 } else { wand_charges.max };
+                        }
+                    }
+                }
+                if entity == *player_entity {
+                    let id = if cursed {
+                        "use.cursed_recharge"
+                    } else {
+                        "use.recharge"
+                    };
+                    game_log
+                        .entry(LogCategory::Item, id)
+                        .name("item", &item_name, item_renderable)
+                        .log();
+                }
+            }
+
+            // amaldiçoado, o pergaminho embaralha o que já se sabia da mochila
+            if identifiers.get(item_user.item).is_some() && entity == *player_entity && cursed {
+                let mut forgotten = 0;
+                for (backpack, kind, _obfuscated) in
+                    (&in_backpacks, &kinds, &obfuscated_names).join()
+                {
+                    if backpack.owner == entity && knowledge.forget(*kind) {
+                        forgotten += 1;
+                    }
+                }
+                game_log
+                    .entry(LogCategory::Item, "use.cursed_identify")
+                    .arg("count", forgotten)
+                    .log();
+            } else if identifiers.get(item_user.item).is_some() && entity == *player_entity {
+                for (item, backpack, name) in (&entities, &in_backpacks, &names).join() {
+                    if backpack.owner != entity {
+                        continue;
+                    }
+                    if obfuscated_names.get(item).is_some()
+                        && kinds
+                            .get(item)
+                            .map_or(false, |kind| knowledge.identify(*kind))
+                    {
+                        game_log
+                            .entry(LogCategory::Item, "use.identified")
+                            .name("item", &name.name, renderables.get(item))
+                            .log();
+                    }
+                    if let Some(curse) = curses.get_mut(item) {
+                        if !curse.known {
+                            curse.known = true;
+                            game_log
+                                .entry(LogCategory::Item, "use.curse_revealed")
+                                .name("item", &name.name, renderables.get(item))
+                                .log();
+                        }
+                    }
+                }
+            }
+
+            // amaldiçoado, o pergaminho amaldiçoa outro item da mochila
+            if curse_removers.get(item_user.item).is_some() && cursed {
+                let candidates = (&entities, &in_backpacks)
+                    .join()
+                    .filter(|(item, backpack)| {
+                        backpack.owner == entity
+                            && *item != item_user.item
+                            && curses.get(*item).is_none()
+                    })
+                    .map(|(item, _backpack)| item)
+                    .collect::<Vec<_>>();
+                match rng.random_slice_entry(&candidates) {
+                    Some(item) => {
+                        curses
+                            .insert(*item, Cursed { known: true })
+                            .expect("não consegui amaldiçoar o item!");
+                        if entity == *player_entity {
+                            let name = knowledge.name_of(
+                                names.get(*item).unwrap(),
+                                kinds.get(*item),
+                                obfuscated_names.get(*item),
+                            );
+                            game_log
+                                .entry(LogCategory::Item, "use.cursed_remove_curse")
+                                .name("item", &name, renderables.get(*item))
+                                .log();
+                        }
+                    }
+                    None => {
+                        if entity == *player_entity {
+                            game_log.item("use.remove_curse_nothing");
+                        }
+                    }
+                }
+            } else if curse_removers.get(item_user.item).is_some() {
+                let uncursed = (&entities, &in_backpacks)
+                    .join()
+                    .filter(|(item, backpack)| {
+                        backpack.owner == entity && curses.get(*item).is_some()
+                    })
+                    .map(|(item, _backpack)| item)
+                    .collect::<Vec<_>>();
+                for item in uncursed.iter() {
+                    curses.remove(*item);
+                }
+                if entity == *player_entity {
+                    if uncursed.is_empty() {
+                        game_log.item("use.remove_curse_nothing");
+                    } else {
+                        game_log
+                            .entry(LogCategory::Item, "use.remove_curse")
+                            .arg("count", uncursed.len())
+                            .log();
+                    }
+                }
+            }
+
+            // usar um item revela o que ele é
+            if entity == *player_entity && obfuscated_names.get(item_user.item).is_some() {
+                let real_name = &names.get(item_user.item).unwrap().name;
+                if kinds
+                    .get(item_user.item)
+                    .map_or(false, |kind| knowledge.identify(*kind))
+                {
+                    game_log
+                        .entry(LogCategory::Item, "use.revealed")
+                        .arg("appearance", &item_name)
+                        .name("item", real_name, item_renderable)
+                        .log();
+                }
+            }
+
+            if let Some(item_charges) = charges.get_mut(item_user.item) {
+                item_charges.current -= 1;
+            }
+
+            if consumables.get(item_user.item).is_some() {
+                match stackables.get_mut(item_user.item) {
+                    Some(stack) if stack.quantity > 1 => stack.quantity -= 1,
+                    _ => entities
+                        .delete(item_user.item)
+                        .expect("não consegui consumir!"),
+                }
             }
         }
 
@@ -178,9 +556,14 @@ impl<'a> System<'a> for ItemDropSystem {
     type SystemData = (
         Entities<'a>,
         ReadExpect<'a, Entity>,
+        ReadExpect<'a, ItemKnowledge>,
         WriteExpect<'a, GameLog>,
         ReadStorage<'a, Name>,
+        ReadStorage<'a, ItemKind>,
+        ReadStorage<'a, ObfuscatedName>,
+        ReadStorage<'a, Renderable>,
         WriteStorage<'a, WantsToDropItem>,
+        WriteStorage<'a, Cursed>,
         WriteStorage<'a, Position>,
         WriteStorage<'a, InBackpack>,
     );
@@ -189,14 +572,35 @@ impl<'a> System<'a> for ItemDropSystem {
         let (
             entities,
             player_entity,
+            knowledge,
             mut game_log,
             names,
+            kinds,
+            obfuscated_names,
+            renderables,
             mut wants_to_drop_items,
+            mut curses,
             mut positions,
             mut in_backpacks,
         ) = data;
 
         for (entity, to_drop) in (&entities, &mut wants_to_drop_items).join() {
+            if let Some(curse) = curses.get_mut(to_drop.item) {
+                curse.known = true;
+                if entity == *player_entity {
+                    let item_name = knowledge.name_of(
+                        names.get(to_drop.item).unwrap(),
+                        kinds.get(to_drop.item),
+                        obfuscated_names.get(to_drop.item),
+                    );
+                    game_log
+                        .entry(LogCategory::Item, "drop.cursed")
+                        .name("item", &item_name, renderables.get(to_drop.item))
+                        .log();
+                }
+                continue;
+            }
+
             let mut dropper_pos = Position { x: 0, y: 0 };
             {
                 let pos = positions.get(entity).unwrap();
@@ -209,13 +613,409 @@ impl<'a> System<'a> for ItemDropSystem {
             in_backpacks.remove(to_drop.item);
 
             if entity == *player_entity {
-                let item_name = names.get(to_drop.item).unwrap();
+                let item_name = knowledge.name_of(
+                    names.get(to_drop.item).unwrap(),
+                    kinds.get(to_drop.item),
+                    obfuscated_names.get(to_drop.item),
+                );
                 game_log
-                    .entries
-                    .push(format!("Você larga {} no chão.", item_name.name));
+                    .entry(LogCategory::Item, "drop.done")
+                    .arg("item", &item_name)
+                    .log();
             }
         }
 
         wants_to_drop_items.clear();
     }
 }
+
+/// Segue a linha de `from` até `to` e devolve onde o projétil cai e quem ele
+/// atinge: para na primeira criatura do caminho ou logo antes de uma parede.
+fn projectile_path<F>(map: &Map, from: Point, to: Point, is_creature: F) -> (Point, Option<Entity>)
+where
+    F: Fn(Entity) -> bool,
+{
+    let mut landing = from;
+    for point in rltk::line2d(LineAlg::Bresenham, from, to) {
+        if point == from {
+            continue;
+        }
+        let idx = map.xy_idx(point.x as usize, point.y as usize);
+        if map.tiles[idx] == TileType::Wall {
+            break;
+        }
+        landing = point;
+        if let Some(victim) = map.tile_content[idx].iter().find(|e| is_creature(**e)) {
+            return (landing, Some(*victim));
+        }
+    }
+    (landing, None)
+}
+
+pub struct ItemThrowSystem {}
+
+impl<'a> System<'a> for ItemThrowSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Entity>,
+        ReadExpect<'a, Map>,
+        ReadExpect<'a, ItemKnowledge>,
+        WriteExpect<'a, GameLog>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        WriteExpect<'a, ParticleBuilder>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, ItemKind>,
+        ReadStorage<'a, ObfuscatedName>,
+        ReadStorage<'a, Renderable>,
+        ReadStorage<'a, Weapon>,
+        ReadStorage<'a, Launcher>,
+        ReadStorage<'a, Attributes>,
+        ReadStorage<'a, CombatStats>,
+        WriteStorage<'a, Cursed>,
+        WriteStorage<'a, WantsToThrowItem>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, InBackpack>,
+        WriteStorage<'a, SufferDamage>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            player_entity,
+            map,
+            knowledge,
+            mut game_log,
+            mut rng,
+            mut particle_builder,
+            names,
+            kinds,
+            obfuscated_names,
+            renderables,
+            weapons,
+            launchers,
+            attributes,
+            combat_stats,
+            mut curses,
+            mut wants_to_throw_items,
+            mut positions,
+            mut in_backpacks,
+            mut suffer_damages,
+        ) = data;
+
+        for (entity, throw) in (&entities, &wants_to_throw_items).join() {
+            let is_player = entity == *player_entity;
+            let item_name = knowledge.name_of(
+                names.get(throw.item).unwrap(),
+                kinds.get(throw.item),
+                obfuscated_names.get(throw.item),
+            );
+            let item_renderable = renderables.get(throw.item);
+
+            if let Some(curse) = curses.get_mut(throw.item) {
+                curse.known = true;
+                if is_player {
+                    game_log
+                        .entry(LogCategory::Item, "throw.cursed")
+                        .name("item", &item_name, item_renderable)
+                        .log();
+                }
+                continue;
+            }
+
+            let from = match positions.get(entity) {
+                Some(pos) => Point::new(pos.x, pos.y),
+                None => continue,
+            };
+            let (landing, victim) = projectile_path(&map, from, throw.target, |e| {
+                e != entity && combat_stats.get(e).is_some()
+            });
+
+            if let Some(render) = renderables.get(throw.item) {
+                particle_builder.projectile(entity, landing, render.fg, render.glyph);
+            }
+
+            in_backpacks.remove(throw.item);
+            positions
+                .insert(
+                    throw.item,
+                    Position {
+                        x: landing.x,
+                        y: landing.y,
+                    },
+                )
+                .expect("o projétil não caiu no mapa!");
+
+            let launcher = throw.launcher.and_then(|l| launchers.get(l));
+            if is_player {
+                match throw.launcher.and_then(|l| names.get(l)) {
+                    Some(launcher_name) => game_log
+                        .entry(LogCategory::Item, "throw.fire")
+                        .name("item", &item_name, item_renderable)
+                        .arg("launcher", &launcher_name.name)
+                        .log(),
+                    None => game_log
+                        .entry(LogCategory::Item, "throw.throw")
+                        .name("item", &item_name, item_renderable)
+                        .log(),
+                }
+            }
+
+            let victim = match victim {
+                Some(victim) => victim,
+                None => {
+                    if is_player {
+                        game_log
+                            .entry(LogCategory::Item, "throw.lands")
+                            .name("item", &item_name, item_renderable)
+                            .log();
+                    }
+                    continue;
+                }
+            };
+            let victim_name = &names.get(victim).unwrap().name;
+
+            let weapon = match weapons.get(throw.item) {
+                Some(weapon) => weapon,
+                None => {
+                    if is_player {
+                        game_log
+                            .entry(LogCategory::Item, "throw.harmless")
+                            .name("item", &item_name, item_renderable)
+                            .name("target", victim_name, renderables.get(victim))
+                            .log();
+                    }
+                    continue;
+                }
+            };
+
+            let hit_bonus = weapon.hit_bonus
+                + launcher.map_or(0, |l| l.hit_bonus)
+                + attributes
+                    .get(entity)
+                    .map_or(0, attributes::ranged_hit_bonus);
+            let armour_class = combat_stats.get(victim).unwrap().armour_class;
+            let damage = match attack_outcome(rng.roll_dice(1, 20), hit_bonus, armour_class) {
+                AttackOutcome::Fumble | AttackOutcome::Miss => {
+                    if is_player {
+                        game_log
+                            .entry(LogCategory::Item, "throw.miss")
+                            .name("item", &item_name, item_renderable)
+                            .name("target", victim_name, renderables.get(victim))
+                            .log();
+                    }
+                    continue;
+                }
+                AttackOutcome::Hit => rng.roll(weapon.damage),
+                AttackOutcome::Critical => rng.roll(DiceType {
+                    n_dice: weapon.damage.n_dice * 2,
+                    ..weapon.damage
+                }),
+            };
+
+            SufferDamage::new_damage(&mut suffer_damages, victim, damage as u32, is_player);
+            particle_builder.request_on(
+                victim,
+                RGB::named(rltk::ORANGE),
+                rltk::to_cp437('‼'),
+                200.0,
+            );
+            if is_player {
+                game_log
+                    .entry(LogCategory::Item, "throw.hit")
+                    .name("item", &item_name, item_renderable)
+                    .name("target", victim_name, renderables.get(victim))
+                    .damage("damage", damage)
+                    .log();
+            }
+        }
+
+        wants_to_throw_items.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spawner;
+
+    fn carried(
+        world: &mut World,
+        owner: Entity,
+        item_fn: fn(&mut World, usize, usize) -> Entity,
+        quantity: u32,
+    ) -> Entity {
+        let item = item_fn(world, 0, 0);
+        world.write_storage::<Position>().remove(item);
+        world
+            .write_storage::<InBackpack>()
+            .insert(item, InBackpack { owner })
+            .unwrap();
+        if let Some(stack) = world.write_storage::<Stackable>().get_mut(item) {
+            stack.quantity = quantity;
+        }
+        item
+    }
+
+    fn curse(world: &mut World, item: Entity) {
+        world
+            .write_storage::<Cursed>()
+            .insert(item, Cursed { known: false })
+            .unwrap();
+    }
+
+    fn use_item(world: &mut World, user: Entity, item: Entity, target: Option<Point>) {
+        world
+            .write_storage::<WantsToUseItem>()
+            .insert(user, WantsToUseItem { item, target })
+            .unwrap();
+        ItemUseSystem {}.run_now(world);
+        world.maintain();
+    }
+
+    fn pick_up(world: &mut World, collected_by: Entity, item: Entity) {
+        world
+            .write_storage::<WantsToPickupItem>()
+            .insert(collected_by, WantsToPickupItem { collected_by, item })
+            .unwrap();
+        ItemCollectionSystem {}.run_now(world);
+        world.maintain();
+    }
+
+    fn quantity(world: &World, item: Entity) -> u32 {
+        world
+            .read_storage::<Stackable>()
+            .get(item)
+            .unwrap()
+            .quantity
+    }
+
+    #[test]
+    fn picking_up_the_same_kind_merges_the_stacks() {
+        let (mut world, player) = spawner::test_world(1);
+        let stack = carried(&mut world, player, spawner::health_potion, 2);
+        let potion = spawner::health_potion(&mut world, 1, 1);
+
+        pick_up(&mut world, player, potion);
+
+        assert_eq!(quantity(&world, stack), 3);
+        assert!(!world.is_alive(potion));
+    }
+
+    #[test]
+    fn cursed_items_get_their_own_stack() {
+        let (mut world, player) = spawner::test_world(1);
+        let stack = carried(&mut world, player, spawner::health_potion, 1);
+        let potion = spawner::health_potion(&mut world, 1, 1);
+        world
+            .write_storage::<Cursed>()
+            .insert(potion, Cursed { known: false })
+            .unwrap();
+
+        pick_up(&mut world, player, potion);
+
+        assert_eq!(quantity(&world, stack), 1);
+        assert!(world.is_alive(potion));
+        assert_eq!(
+            world
+                .read_storage::<InBackpack>()
+                .get(potion)
+                .unwrap()
+                .owner,
+            player
+        );
+    }
+
+    #[test]
+    fn splitting_takes_one_out_of_the_stack() {
+        let (mut world, player) = spawner::test_world(1);
+        let stack = carried(&mut world, player, spawner::arrow, 3);
+
+        let arrow = spawner::split_stack(&mut world, stack);
+
+        assert_ne!(arrow, stack);
+        assert_eq!(quantity(&world, stack), 2);
+        assert_eq!(quantity(&world, arrow), 1);
+        assert_eq!(
+            world.read_storage::<InBackpack>().get(arrow).unwrap().owner,
+            player
+        );
+        let kinds = world.read_storage::<ItemKind>();
+        assert_eq!(kinds.get(arrow), kinds.get(stack));
+    }
+
+    #[test]
+    fn a_single_item_is_not_split() {
+        let (mut world, player) = spawner::test_world(1);
+        let stack = carried(&mut world, player, spawner::arrow, 1);
+        assert_eq!(spawner::split_stack(&mut world, stack), stack);
+    }
+
+    #[test]
+    fn using_one_of_a_stack_keeps_the_rest() {
+        let (mut world, player) = spawner::test_world(1);
+        let stack = carried(&mut world, player, spawner::health_potion, 2);
+
+        use_item(&mut world, player, stack, None);
+        assert_eq!(quantity(&world, stack), 1);
+
+        use_item(&mut world, player, stack, None);
+        assert!(!world.is_alive(stack));
+    }
+
+    #[test]
+    fn cursed_damage_hits_the_user_without_credit() {
+        let (mut world, player) = spawner::test_world(1);
+        let scroll = carried(&mut world, player, spawner::magic_missile_scroll, 1);
+        curse(&mut world, scroll);
+
+        use_item(&mut world, player, scroll, Some(Point::new(5, 5)));
+
+        let damages = world.read_storage::<SufferDamage>();
+        assert!(!damages.get(player).unwrap().from_player);
+    }
+
+    #[test]
+    fn cursed_recharging_drains_the_wands() {
+        let (mut world, player) = spawner::test_world(1);
+        let wand = carried(&mut world, player, spawner::confusion_wand, 1);
+        let scroll = carried(&mut world, player, spawner::recharging_scroll, 1);
+        curse(&mut world, scroll);
+
+        use_item(&mut world, player, scroll, None);
+
+        assert_eq!(
+            world.read_storage::<Charges>().get(wand).unwrap().current,
+            0
+        );
+    }
+
+    #[test]
+    fn cursed_identify_forgets_what_was_known() {
+        let (mut world, player) = spawner::test_world(1);
+        let wand = carried(&mut world, player, spawner::confusion_wand, 1);
+        let kind = *world.read_storage::<ItemKind>().get(wand).unwrap();
+        world.fetch_mut::<ItemKnowledge>().identify(kind);
+        let scroll = carried(&mut world, player, spawner::identify_scroll, 1);
+        curse(&mut world, scroll);
+
+        use_item(&mut world, player, scroll, None);
+
+        assert!(!world.fetch::<ItemKnowledge>().is_identified(kind));
+    }
+
+    #[test]
+    fn cursed_remove_curse_curses_another_item() {
+        let (mut world, player) = spawner::test_world(1);
+        let dagger = carried(&mut world, player, spawner::dagger, 1);
+        let potion = carried(&mut world, player, spawner::health_potion, 1);
+        curse(&mut world, potion);
+        let scroll = carried(&mut world, player, spawner::remove_curse_scroll, 1);
+        curse(&mut world, scroll);
+
+        use_item(&mut world, player, scroll, None);
+
+        let curses = world.read_storage::<Cursed>();
+        assert!(curses.get(dagger).is_some());
+        assert!(curses.get(potion).is_some());
+    }
+}
@@ -0,0 +1,481 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+
+use rltk::VirtualKeyCode;
+
+use crate::i18n::Text;
+use crate::i18n::ToText;
+
+/// Arquivo lido na inicialização com as teclas escolhidas pelo jogador.
+pub const CONFIG_PATH: &str = "keybindings.cfg";
+
+/// O que o jogador quer fazer, independente da tecla que apertou.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    MoveWest,
+    MoveEast,
+    MoveNorth,
+    MoveSouth,
+    MoveNorthEast,
+    MoveNorthWest,
+    MoveSouthEast,
+    MoveSouthWest,
+    Wait,
+    Rest,
+    PickUp,
+    Inventory,
+    Drop,
+    Throw,
+    Companions,
+    CharacterSheet,
+    Spells,
+    Look,
+    Explore,
+    MessageLog,
+    Cancel,
+    Confirm,
+    NextTarget,
+    PageUp,
+    PageDown,
+    ScrollTop,
+    ScrollBottom,
+    Search,
+    ToggleLogSave,
+}
+
+use Action::*;
+
+/// Tela em que a tecla foi apertada. Cada uma só enxerga as próprias
+/// ações, então a mesma tecla pode fazer uma coisa no mapa e outra no
+/// registro de mensagens.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum KeyContext {
+    Gameplay,
+    Menu,
+    Targeting,
+    LogViewer,
+}
+
+const CONTEXTS: [KeyContext; 4] = [
+    KeyContext::Gameplay,
+    KeyContext::Menu,
+    KeyContext::Targeting,
+    KeyContext::LogViewer,
+];
+
+impl KeyContext {
+    fn actions(self) -> &'static [Action] {
+        match self {
+            KeyContext::Gameplay => &[
+                MoveWest,
+                MoveEast,
+                MoveNorth,
+                MoveSouth,
+                MoveNorthEast,
+                MoveNorthWest,
+                MoveSouthEast,
+                MoveSouthWest,
+                Wait,
+                Rest,
+                PickUp,
+                Inventory,
+                Drop,
+                Throw,
+                Companions,
+                CharacterSheet,
+                Spells,
+                Look,
+                Explore,
+                MessageLog,
+            ],
+            KeyContext::Menu => &[MoveNorth, MoveSouth, Confirm, Cancel],
+            // mira e modo de olhar
+            KeyContext::Targeting => &[
+                MoveWest,
+                MoveEast,
+                MoveNorth,
+                MoveSouth,
+                MoveNorthEast,
+                MoveNorthWest,
+                MoveSouthEast,
+                MoveSouthWest,
+                NextTarget,
+                Look,
+                Confirm,
+                Cancel,
+            ],
+            KeyContext::LogViewer => &[
+                MoveNorth,
+                MoveSouth,
+                PageUp,
+                PageDown,
+                ScrollTop,
+                ScrollBottom,
+                Search,
+                ToggleLogSave,
+                NextTarget,
+                Cancel,
+            ],
+        }
+    }
+}
+
+const DEFAULT_BINDINGS: [(Action, &[VirtualKeyCode]); 29] = [
+    (
+        MoveWest,
+        &[
+            VirtualKeyCode::Left,
+            VirtualKeyCode::H,
+            VirtualKeyCode::Numpad4,
+        ],
+    ),
+    (
+        MoveEast,
+        &[
+            VirtualKeyCode::Right,
+            VirtualKeyCode::L,
+            VirtualKeyCode::Numpad6,
+        ],
+    ),
+    (
+        MoveNorth,
+        &[
+            VirtualKeyCode::Up,
+            VirtualKeyCode::K,
+            VirtualKeyCode::Numpad8,
+        ],
+    ),
+    (
+        MoveSouth,
+        &[
+            VirtualKeyCode::Down,
+            VirtualKeyCode::J,
+            VirtualKeyCode::Numpad2,
+        ],
+    ),
+    (MoveNorthEast, &[VirtualKeyCode::U, VirtualKeyCode::Numpad9]),
+    (MoveNorthWest, &[VirtualKeyCode::Y, VirtualKeyCode::Numpad7]),
+    (MoveSouthEast, &[VirtualKeyCode::N, VirtualKeyCode::Numpad3]),
+    (MoveSouthWest, &[VirtualKeyCode::B, VirtualKeyCode::Numpad1]),
+    (Wait, &[VirtualKeyCode::Period, VirtualKeyCode::Numpad5]),
+    (Rest, &[VirtualKeyCode::R]),
+    (PickUp, &[VirtualKeyCode::G]),
+    (Inventory, &[VirtualKeyCode::I]),
+    (Drop, &[VirtualKeyCode::D]),
+    (Throw, &[VirtualKeyCode::T]),
+    (Companions, &[VirtualKeyCode::C]),
+    (CharacterSheet, &[VirtualKeyCode::S]),
+    (Spells, &[VirtualKeyCode::Z]),
+    (Look, &[VirtualKeyCode::X]),
+    (Explore, &[VirtualKeyCode::O]),
+    (MessageLog, &[VirtualKeyCode::M]),
+    (Cancel, &[VirtualKeyCode::Escape]),
+    (
+        Confirm,
+        &[VirtualKeyCode::Return, VirtualKeyCode::NumpadEnter],
+    ),
+    (NextTarget, &[VirtualKeyCode::Tab]),
+    (PageUp, &[VirtualKeyCode::PageUp]),
+    (PageDown, &[VirtualKeyCode::PageDown]),
+    (ScrollTop, &[VirtualKeyCode::Home]),
+    (ScrollBottom, &[VirtualKeyCode::End]),
+    (Search, &[VirtualKeyCode::Slash]),
+    (ToggleLogSave, &[VirtualKeyCode::F]),
+];
+
+/// Teclas que podem aparecer no arquivo de configuração, pelo nome.
+const NAMED_KEYS: [VirtualKeyCode; 76] = [
+    VirtualKeyCode::A,
+    VirtualKeyCode::B,
+    VirtualKeyCode::C,
+    VirtualKeyCode::D,
+    VirtualKeyCode::E,
+    VirtualKeyCode::F,
+    VirtualKeyCode::G,
+    VirtualKeyCode::H,
+    VirtualKeyCode::I,
+    VirtualKeyCode::J,
+    VirtualKeyCode::K,
+    VirtualKeyCode::L,
+    VirtualKeyCode::M,
+    VirtualKeyCode::N,
+    VirtualKeyCode::O,
+    VirtualKeyCode::P,
+    VirtualKeyCode::Q,
+    VirtualKeyCode::R,
+    VirtualKeyCode::S,
+    VirtualKeyCode::T,
+    VirtualKeyCode::U,
+    VirtualKeyCode::V,
+    VirtualKeyCode::W,
+    VirtualKeyCode::X,
+    VirtualKeyCode::Y,
+    VirtualKeyCode::Z,
+    VirtualKeyCode::Key0,
+    VirtualKeyCode::Key1,
+    VirtualKeyCode::Key2,
+    VirtualKeyCode::Key3,
+    VirtualKeyCode::Key4,
+    VirtualKeyCode::Key5,
+    VirtualKeyCode::Key6,
+    VirtualKeyCode::Key7,
+    VirtualKeyCode::Key8,
+    VirtualKeyCode::Key9,
+    VirtualKeyCode::Numpad0,
+    VirtualKeyCode::Numpad1,
+    VirtualKeyCode::Numpad2,
+    VirtualKeyCode::Numpad3,
+    VirtualKeyCode::Numpad4,
+    VirtualKeyCode::Numpad5,
+    VirtualKeyCode::Numpad6,
+    VirtualKeyCode::Numpad7,
+    VirtualKeyCode::Numpad8,
+    VirtualKeyCode::Numpad9,
+    VirtualKeyCode::NumpadEnter,
+    VirtualKeyCode::Add,
+    VirtualKeyCode::Subtract,
+    VirtualKeyCode::Multiply,
+    VirtualKeyCode::Divide,
+    VirtualKeyCode::Decimal,
+    VirtualKeyCode::Left,
+    VirtualKeyCode::Right,
+    VirtualKeyCode::Up,
+    VirtualKeyCode::Down,
+    VirtualKeyCode::Home,
+    VirtualKeyCode::End,
+    VirtualKeyCode::PageUp,
+    VirtualKeyCode::PageDown,
+    VirtualKeyCode::Insert,
+    VirtualKeyCode::Delete,
+    VirtualKeyCode::Escape,
+    VirtualKeyCode::Return,
+    VirtualKeyCode::Tab,
+    VirtualKeyCode::Space,
+    VirtualKeyCode::Back,
+    VirtualKeyCode::Period,
+    VirtualKeyCode::Comma,
+    VirtualKeyCode::Semicolon,
+    VirtualKeyCode::Colon,
+    VirtualKeyCode::Slash,
+    VirtualKeyCode::Backslash,
+    VirtualKeyCode::Minus,
+    VirtualKeyCode::Equals,
+    VirtualKeyCode::Apostrophe,
+];
+
+impl Action {
+    /// Nome usado no arquivo de configuração.
+    pub fn name(self) -> &'static str {
+        match self {
+            MoveWest => "move_west",
+            MoveEast => "move_east",
+            MoveNorth => "move_north",
+            MoveSouth => "move_south",
+            MoveNorthEast => "move_north_east",
+            MoveNorthWest => "move_north_west",
+            MoveSouthEast => "move_south_east",
+            MoveSouthWest => "move_south_west",
+            Wait => "wait",
+            Rest => "rest",
+            PickUp => "pick_up",
+            Inventory => "inventory",
+            Drop => "drop",
+            Throw => "throw",
+            Companions => "companions",
+            CharacterSheet => "character_sheet",
+            Spells => "spells",
+            Look => "look",
+            Explore => "explore",
+            MessageLog => "message_log",
+            Cancel => "cancel",
+            Confirm => "confirm",
+            NextTarget => "next_target",
+            PageUp => "page_up",
+            PageDown => "page_down",
+            ScrollTop => "scroll_top",
+            ScrollBottom => "scroll_bottom",
+            Search => "search",
+            ToggleLogSave => "toggle_log_save",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        DEFAULT_BINDINGS
+            .iter()
+            .map(|(action, _keys)| *action)
+            .find(|action| action.name() == name)
+    }
+
+    /// Deslocamento das ações de movimento.
+    pub fn direction(self) -> Option<(i32, i32)> {
+        match self {
+            MoveWest => Some((-1, 0)),
+            MoveEast => Some((1, 0)),
+            MoveNorth => Some((0, -1)),
+            MoveSouth => Some((0, 1)),
+            MoveNorthEast => Some((1, -1)),
+            MoveNorthWest => Some((-1, -1)),
+            MoveSouthEast => Some((1, 1)),
+            MoveSouthWest => Some((-1, 1)),
+            _ => None,
+        }
+    }
+}
+
+fn key_from_name(name: &str) -> Option<VirtualKeyCode> {
+    NAMED_KEYS
+        .iter()
+        .find(|key| format!("{:?}", key).eq_ignore_ascii_case(name))
+        .copied()
+}
+
+/// Qual ação cada tecla dispara, em cada tela.
+pub struct KeyMap {
+    bindings: HashMap<KeyContext, HashMap<VirtualKeyCode, Action>>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self::from_config("").expect("as teclas padrão não podem conflitar!")
+    }
+}
+
+impl KeyMap {
+    pub fn action(&self, context: KeyContext, key: Option<VirtualKeyCode>) -> Option<Action> {
+        key.and_then(|key| self.bindings.get(&context)?.get(&key).copied())
+    }
+
+    /// Parte das teclas padrão e aplica as linhas `ação = tecla, tecla` do
+    /// texto; cada linha substitui todas as teclas daquela ação. `#` começa
+    /// um comentário. Uma tecla ligada a duas ações da mesma tela é um erro.
+    pub fn from_config(text: &str) -> Result<Self, Text> {
+        let mut keys: Vec<(Action, Vec<VirtualKeyCode>)> = DEFAULT_BINDINGS
+            .iter()
+            .map(|(action, keys)| (*action, keys.to_vec()))
+            .collect();
+
+        for (n, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (name, key_names) = line
+                .split_once('=')
+                .ok_or_else(|| Text::message("keys.error.syntax").arg("line", n + 1))?;
+            let action = Action::from_name(name.trim()).ok_or_else(|| {
+                Text::message("keys.error.unknown_action")
+                    .arg("line", n + 1)
+                    .arg("action", name.trim())
+            })?;
+            let mut bound = Vec::new();
+            for key_name in key_names.split(',').map(str::trim) {
+                if key_name.is_empty() {
+                    continue;
+                }
+                let key = key_from_name(key_name).ok_or_else(|| {
+                    Text::message("keys.error.unknown_key")
+                        .arg("line", n + 1)
+                        .arg("key", key_name)
+                })?;
+                bound.push(key);
+            }
+            if let Some(entry) = keys.iter_mut().find(|(a, _keys)| *a == action) {
+                entry.1 = bound;
+            }
+        }
+
+        let mut bindings = HashMap::new();
+        for context in CONTEXTS.iter() {
+            let mut context_bindings = HashMap::new();
+            for (action, action_keys) in keys
+                .iter()
+                .filter(|(action, _keys)| context.actions().contains(action))
+            {
+                for key in action_keys.iter() {
+                    if let Some(other) = context_bindings.insert(*key, *action) {
+                        if other != *action {
+                            return Err(Text::message("keys.error.conflict")
+                                .arg("key", format!("{:?}", key))
+                                .arg("first", other.name())
+                                .arg("second", action.name()));
+                        }
+                    }
+                }
+            }
+            bindings.insert(*context, context_bindings);
+        }
+        Ok(Self { bindings })
+    }
+
+    /// Lê `CONFIG_PATH`; sem o arquivo valem as teclas padrão. Se o arquivo
+    /// tiver problemas, também ficam as padrão e o erro é devolvido junto.
+    pub fn load() -> (Self, Option<Text>) {
+        match fs::read_to_string(CONFIG_PATH) {
+            Ok(text) => match Self::from_config(&text) {
+                Ok(keymap) => (keymap, None),
+                Err(error) => (Self::default(), Some(error)),
+            },
+            Err(error) if error.kind() == ErrorKind::NotFound => (Self::default(), None),
+            Err(error) => (Self::default(), Some(error.to_text())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_replace_the_defaults() {
+        let keymap =
+            KeyMap::from_config("move_north = Z\nspells = K\nmove_west = Q, Numpad4 # azerty\n")
+                .unwrap_or_else(|e| panic!("{:?}", e));
+        let gameplay = |key| keymap.action(KeyContext::Gameplay, Some(key));
+        assert_eq!(gameplay(VirtualKeyCode::Z), Some(MoveNorth));
+        assert_eq!(gameplay(VirtualKeyCode::Q), Some(MoveWest));
+        assert_eq!(gameplay(VirtualKeyCode::K), Some(Spells));
+        assert_eq!(gameplay(VirtualKeyCode::H), None);
+    }
+
+    #[test]
+    fn example_config_is_valid() {
+        let keymap = KeyMap::from_config(include_str!("../keybindings.example.cfg"))
+            .unwrap_or_else(|e| panic!("{:?}", e));
+        assert_eq!(
+            keymap.action(KeyContext::Gameplay, Some(VirtualKeyCode::Z)),
+            Some(MoveNorth)
+        );
+    }
+
+    #[test]
+    fn the_same_key_can_differ_between_contexts() {
+        let keymap =
+            KeyMap::from_config("character_sheet = F").unwrap_or_else(|e| panic!("{:?}", e));
+        let f = Some(VirtualKeyCode::F);
+        assert_eq!(keymap.action(KeyContext::Gameplay, f), Some(CharacterSheet));
+        assert_eq!(keymap.action(KeyContext::LogViewer, f), Some(ToggleLogSave));
+        assert_eq!(keymap.action(KeyContext::Menu, f), None);
+    }
+
+    #[test]
+    fn every_action_belongs_to_a_context() {
+        for (action, _keys) in DEFAULT_BINDINGS.iter() {
+            assert!(
+                CONTEXTS
+                    .iter()
+                    .any(|context| context.actions().contains(action)),
+                "{}",
+                action.name()
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_conflicts_and_unknown_names() {
+        assert!(KeyMap::from_config("wait = G").is_err());
+        assert!(KeyMap::from_config("dance = G").is_err());
+        assert!(KeyMap::from_config("wait = Banana").is_err());
+        assert!(KeyMap::from_config("wait").is_err());
+    }
+}
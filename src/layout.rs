@@ -0,0 +1,78 @@
+/// Tamanho do console inteiro, igual ao de `RltkBuilder::simple80x50`.
+pub const CONSOLE_WIDTH: i32 = 80;
+pub const CONSOLE_HEIGHT: i32 = 50;
+pub const SIDEBAR_WIDTH: i32 = 24;
+pub const LOG_HEIGHT: i32 = 7;
+
+/// Retângulo do console, em células.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Region {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Region {
+    pub fn contains(self, x: i32, y: i32) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+}
+
+/// Onde fica cada parte da tela de jogo: o mapa no canto, o registro de
+/// mensagens embaixo dele e a barra lateral à direita, de cima a baixo.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Layout {
+    pub map: Region,
+    pub log: Region,
+    pub sidebar: Region,
+}
+
+impl Layout {
+    pub fn new(console_width: i32, console_height: i32) -> Self {
+        let map = Region {
+            x: 0,
+            y: 0,
+            width: console_width - SIDEBAR_WIDTH,
+            height: console_height - LOG_HEIGHT,
+        };
+        Self {
+            map,
+            log: Region {
+                x: 0,
+                y: map.height,
+                width: map.width,
+                height: LOG_HEIGHT,
+            },
+            sidebar: Region {
+                x: map.width,
+                y: 0,
+                width: SIDEBAR_WIDTH,
+                height: console_height,
+            },
+        }
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::new(CONSOLE_WIDTH, CONSOLE_HEIGHT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regions_cover_the_console() {
+        let layout = Layout::default();
+        let cells = [layout.map, layout.log, layout.sidebar]
+            .iter()
+            .map(|r| r.width * r.height)
+            .sum::<i32>();
+        assert_eq!(cells, CONSOLE_WIDTH * CONSOLE_HEIGHT);
+        assert!(!layout.map.contains(layout.sidebar.x, 0));
+        assert!(layout.log.contains(0, CONSOLE_HEIGHT - 1));
+    }
+}
@@ -5,42 +5,82 @@ extern crate specs_derive;
 #[macro_use]
 extern crate quickcheck_macros;
 
+mod area_of_effect;
+mod attributes;
+mod camera;
+mod companion_ai_system;
 mod components;
 mod damage_system;
+mod encumbrance_system;
+mod faction;
 mod game_log;
 mod gui;
+mod i18n;
+mod identification;
 mod inventory_system;
+mod keybindings;
+mod layout;
+mod mana_system;
 mod map;
 mod map_indexing_system;
 mod melee_combat_system;
 mod monster_ai_system;
+mod particle_system;
+mod perception_system;
 mod player;
+mod regeneration_system;
 mod spawner;
+mod tileset;
+mod travel;
 mod visibility_system;
 
 use rltk::GameState;
 use rltk::Point;
 use rltk::Rltk;
 use rltk::RltkBuilder;
+use rltk::RGB;
 use specs::prelude::*;
 
+use crate::attributes::Encumbrance;
+use crate::camera::Camera;
+use crate::companion_ai_system::CompanionAISystem;
 use crate::components::*;
 use crate::damage_system::DamageSystem;
+use crate::encumbrance_system::EncumbranceSystem;
 use crate::game_log::GameLog;
+use crate::game_log::LogCategory;
 use crate::gui::draw_ui;
 use crate::gui::MainMenuResult;
 use crate::gui::MainMenuSelection;
+use crate::i18n::Catalogue;
+use crate::i18n::Language;
 use crate::inventory_system::ItemCollectionSystem;
 use crate::inventory_system::ItemDropSystem;
+use crate::inventory_system::ItemThrowSystem;
 use crate::inventory_system::ItemUseSystem;
+use crate::inventory_system::THROW_RANGE;
+use crate::layout::CONSOLE_HEIGHT;
+use crate::layout::CONSOLE_WIDTH;
+use crate::mana_system::ManaRegenSystem;
 use crate::map::*;
 use crate::map_indexing_system::MapIndexingSystem;
 use crate::melee_combat_system::MeleeCombatSystem;
 use crate::monster_ai_system::MonsterAISystem;
+use crate::particle_system::ParticleBuilder;
+use crate::particle_system::ParticleSpawnSystem;
+use crate::perception_system::PerceptionSystem;
+use crate::perception_system::PlayerNoise;
 use crate::player::*;
+use crate::regeneration_system::RegenerationClock;
+use crate::regeneration_system::RegenerationSystem;
+use crate::tileset::EntityKind;
+use crate::tileset::RenderMode;
+use crate::tileset::ASCII_CONSOLE;
+use crate::tileset::TILE_CONSOLE;
 use crate::visibility_system::VisibilitySystem;
 
 rltk::add_wasm_support!();
+rltk::embedded_resource!(TILE_FONT_IMAGE, "../resources/tiles.png");
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum RunState {
@@ -51,7 +91,23 @@ pub enum RunState {
     MonsterTurn,
     ShowInventory,
     ShowDropItem,
-    ShowTargeting { range: u32, item: Entity },
+    ShowTargeting {
+        range: u32,
+        item: Entity,
+    },
+    ShowThrowItem,
+    ShowThrowTargeting {
+        range: u32,
+        item: Entity,
+        launcher: Option<Entity>,
+    },
+    ShowCharacterSheet,
+    CharacterCreation,
+    ShowSpells,
+    LookMode,
+    ShowLog,
+    Settings,
+    GameOver,
 }
 
 pub struct State {
@@ -59,11 +115,17 @@ pub struct State {
 }
 
 impl State {
-    fn run_systems(&mut self) {
+    /// Tudo o que as criaturas fazem num turno: perceber, decidir, andar,
+    /// lutar e usar itens.
+    fn run_action_systems(&mut self) {
         let mut visibility_system = VisibilitySystem {};
         visibility_system.run_now(&self.ecs);
+        let mut perception_system = PerceptionSystem {};
+        perception_system.run_now(&self.ecs);
         let mut monster_ai_system = MonsterAISystem {};
         monster_ai_system.run_now(&self.ecs);
+        let mut companion_ai_system = CompanionAISystem {};
+        companion_ai_system.run_now(&self.ecs);
         let mut map_indexing_system = MapIndexingSystem {};
         map_indexing_system.run_now(&self.ecs);
         let mut melee_combat_system = MeleeCombatSystem {};
@@ -74,15 +136,130 @@ impl State {
         item_collection_system.run_now(&self.ecs);
         let mut item_use_system = ItemUseSystem {};
         item_use_system.run_now(&self.ecs);
+        let mut item_throw_system = ItemThrowSystem {};
+        item_throw_system.run_now(&self.ecs);
         let mut item_drop_system = ItemDropSystem {};
         item_drop_system.run_now(&self.ecs);
+    }
+
+    fn run_systems(&mut self) {
+        self.run_action_systems();
+        let mut encumbrance_system = EncumbranceSystem {};
+        encumbrance_system.run_now(&self.ecs);
+        let mut mana_regen_system = ManaRegenSystem {};
+        mana_regen_system.run_now(&self.ecs);
+        let mut regeneration_system = RegenerationSystem {};
+        regeneration_system.run_now(&self.ecs);
+        let mut particle_spawn_system = ParticleSpawnSystem {};
+        particle_spawn_system.run_now(&self.ecs);
+        self.ecs.maintain();
+    }
+
+    /// Uma ação a mais para as outras criaturas, sem regenerar nem passar o
+    /// tempo do resto do mundo.
+    fn run_monster_action(&mut self) {
+        self.run_action_systems();
+        let mut particle_spawn_system = ParticleSpawnSystem {};
+        particle_spawn_system.run_now(&self.ecs);
         self.ecs.maintain();
     }
+
+    fn player_encumbrance(&self) -> Encumbrance {
+        let player_entity = self.ecs.fetch::<Entity>();
+        self.ecs
+            .read_storage::<Burden>()
+            .get(*player_entity)
+            .map_or(Encumbrance::Unburdened, |b| {
+                attributes::encumbrance(b.load, b.capacity)
+            })
+    }
+
+    /// Primeira pilha de munição do tipo pedido na mochila do jogador.
+    fn find_ammunition(&self, kind: AmmoKind) -> Option<Entity> {
+        let entities = self.ecs.entities();
+        let player_entity = self.ecs.fetch::<Entity>();
+        let backpacks = self.ecs.read_storage::<InBackpack>();
+        let ammunitions = self.ecs.read_storage::<Ammunition>();
+        (&entities, &backpacks, &ammunitions)
+            .join()
+            .find(|(_ammo, backpack, ammunition)| {
+                backpack.owner == *player_entity && ammunition.kind == kind
+            })
+            .map(|(ammo, ..)| ammo)
+    }
+
+    /// Itens e magias com alcance pedem um alvo; os demais são usados na hora.
+    /// Arcos e bestas miram com a munição que houver na mochila.
+    fn use_or_target(&mut self, item_entity: Entity) -> RunState {
+        // varinha vazia nem chega a mirar, e o turno não é gasto
+        let charges = self.ecs.read_storage::<Charges>();
+        if let Some(Charges { current: 0, .. }) = charges.get(item_entity) {
+            let names = self.ecs.read_storage::<Name>();
+            let kinds = self.ecs.read_storage::<ItemKind>();
+            let obfuscated_names = self.ecs.read_storage::<ObfuscatedName>();
+            let renderables = self.ecs.read_storage::<Renderable>();
+            let item_name = self.ecs.fetch::<identification::ItemKnowledge>().name_of(
+                names.get(item_entity).unwrap(),
+                kinds.get(item_entity),
+                obfuscated_names.get(item_entity),
+            );
+            self.ecs
+                .fetch_mut::<GameLog>()
+                .entry(LogCategory::Item, "use.no_charges")
+                .name("item", &item_name, renderables.get(item_entity))
+                .log();
+            return RunState::AwaitingInput;
+        }
+
+        let launcher = self
+            .ecs
+            .read_storage::<Launcher>()
+            .get(item_entity)
+            .map(|l| (l.ammo, l.range));
+        if let Some((ammo, range)) = launcher {
+            return match self.find_ammunition(ammo) {
+                Some(ammo) => RunState::ShowThrowTargeting {
+                    range,
+                    item: ammo,
+                    launcher: Some(item_entity),
+                },
+                None => {
+                    self.ecs.fetch_mut::<GameLog>().item("throw.no_ammo");
+                    RunState::AwaitingInput
+                }
+            };
+        }
+
+        let mut wants_to_use_items = self.ecs.write_storage::<WantsToUseItem>();
+        let player_entity = self.ecs.fetch::<Entity>();
+        let ranged_items = self.ecs.read_storage::<Ranged>();
+        if let Some(ranged) = ranged_items.get(item_entity) {
+            RunState::ShowTargeting {
+                range: ranged.range,
+                item: item_entity,
+            }
+        } else {
+            wants_to_use_items
+                .insert(
+                    *player_entity,
+                    WantsToUseItem {
+                        item: item_entity,
+                        target: None,
+                    },
+                )
+                .expect("nao consegui criar a vontade de usar!");
+            RunState::PlayerTurn
+        }
+    }
 }
 
 impl GameState for State {
     fn tick(&mut self, ctx: &mut Rltk) {
+        ctx.set_active_console(TILE_CONSOLE);
+        ctx.cls();
+        ctx.set_active_console(ASCII_CONSOLE);
         ctx.cls();
+        particle_system::cull_dead_particles(&mut self.ecs, ctx.frame_time_ms);
 
         draw_map(&self.ecs, ctx);
         draw_ui(&self.ecs, ctx);
@@ -90,13 +267,47 @@ impl GameState for State {
         {
             let positions = self.ecs.read_storage::<Position>();
             let renderables = self.ecs.read_storage::<Renderable>();
+            let players = self.ecs.read_storage::<Player>();
+            let items = self.ecs.read_storage::<Item>();
+            let particles = self.ecs.read_storage::<ParticleLifetime>();
             let map = self.ecs.fetch::<Map>();
+            let camera = Camera::from_ecs(&self.ecs);
+            let tileset = self.ecs.fetch::<RenderMode>().tileset();
 
-            let mut data = (&positions, &renderables).join().collect::<Vec<_>>();
-            data.sort_by(|(_p1, r1), (_p2, r2)| r2.render_order.cmp(&r1.render_order));
-            for (pos, render) in data.iter() {
-                if map.visible_tiles[xy_idx(pos.x, pos.y)] {
-                    ctx.set(pos.x, pos.y, render.fg, render.bg, render.glyph);
+            let mut data = (&self.ecs.entities(), &positions, &renderables)
+                .join()
+                .collect::<Vec<_>>();
+            data.sort_by_key(|(_e, _p, r)| std::cmp::Reverse(r.render_order));
+            for (entity, pos, render) in data.iter() {
+                if !map.visible_tiles[map.xy_idx(pos.x as usize, pos.y as usize)] {
+                    continue;
+                }
+                let screen = match camera.to_screen(Point::new(pos.x, pos.y)) {
+                    Some(screen) => screen,
+                    None => continue,
+                };
+                let kind = if players.contains(*entity) {
+                    EntityKind::Player
+                } else if particles.contains(*entity) {
+                    EntityKind::Particle
+                } else if items.contains(*entity) {
+                    EntityKind::Item
+                } else {
+                    EntityKind::Creature
+                };
+                match tileset.sprite(kind) {
+                    Some(glyph) => {
+                        ctx.set_active_console(tileset.console);
+                        ctx.set(
+                            screen.x,
+                            screen.y,
+                            RGB::named(rltk::WHITE),
+                            render.bg,
+                            glyph,
+                        );
+                        ctx.set_active_console(ASCII_CONSOLE);
+                    }
+                    None => ctx.set(screen.x, screen.y, render.fg, render.bg, render.glyph),
                 }
             }
         }
@@ -115,33 +326,26 @@ impl GameState for State {
             }
             RunState::MonsterTurn => {
                 self.run_systems();
+                // sobrecarregado, o jogador é lento e os monstros agem de novo
+                if self.player_encumbrance() == Encumbrance::Overloaded {
+                    self.run_monster_action();
+                }
+                self.ecs.fetch_mut::<GameLog>().turn += 1;
                 RunState::AwaitingInput
             }
             RunState::ShowInventory => match gui::show_inventory(self, ctx) {
                 gui::ItemMenuResult::Cancel => RunState::AwaitingInput,
                 gui::ItemMenuResult::NoResponse => RunState::ShowInventory,
                 gui::ItemMenuResult::Selected((item_entity, _item_name)) => {
-                    // TODO check when other items exist
-                    let mut wants_to_use_items = self.ecs.write_storage::<WantsToUseItem>();
-                    let player_entity = self.ecs.fetch::<Entity>();
-                    let ranged_items = self.ecs.read_storage::<Ranged>();
-                    if let Some(ranged) = ranged_items.get(item_entity) {
-                        RunState::ShowTargeting {
-                            range: ranged.range,
-                            item: item_entity,
-                        }
-                    } else {
-                        wants_to_use_items
-                            .insert(
-                                *player_entity,
-                                WantsToUseItem {
-                                    item: item_entity,
-                                    target: None,
-                                },
-                            )
-                            .expect("nao consegui criar a vontade de usar!");
-                        RunState::PlayerTurn
-                    }
+                    self.use_or_target(item_entity)
+                }
+                gui::ItemMenuResult::RangeSelected(_) => RunState::PlayerTurn,
+            },
+            RunState::ShowSpells => match gui::cast_spell_menu(self, ctx) {
+                gui::ItemMenuResult::Cancel => RunState::AwaitingInput,
+                gui::ItemMenuResult::NoResponse => RunState::ShowSpells,
+                gui::ItemMenuResult::Selected((spell_entity, _spell_name)) => {
+                    self.use_or_target(spell_entity)
                 }
                 gui::ItemMenuResult::RangeSelected(_) => RunState::PlayerTurn,
             },
@@ -149,6 +353,12 @@ impl GameState for State {
                 gui::ItemMenuResult::Cancel => RunState::AwaitingInput,
                 gui::ItemMenuResult::NoResponse => RunState::ShowDropItem,
                 gui::ItemMenuResult::Selected((item_entity, _item_name)) => {
+                    // com SHIFT a pilha inteira vai para o chão
+                    let item_entity = if ctx.shift {
+                        item_entity
+                    } else {
+                        spawner::split_stack(&mut self.ecs, item_entity)
+                    };
                     let mut intent = self.ecs.write_storage::<WantsToDropItem>();
                     let player_entity = self.ecs.fetch::<Entity>();
                     intent
@@ -158,35 +368,147 @@ impl GameState for State {
                 }
                 gui::ItemMenuResult::RangeSelected(_) => RunState::PlayerTurn,
             },
-            RunState::ShowTargeting { range, item } => match gui::ranged_target(self, ctx, range) {
+            RunState::ShowTargeting { range, item } => {
+                match gui::ranged_target(self, ctx, range, item) {
+                    gui::ItemMenuResult::Cancel => RunState::AwaitingInput,
+                    gui::ItemMenuResult::NoResponse => RunState::ShowTargeting { range, item },
+                    gui::ItemMenuResult::Selected(_) => RunState::AwaitingInput,
+                    gui::ItemMenuResult::RangeSelected(target) => {
+                        let mut intent = self.ecs.write_storage::<WantsToUseItem>();
+                        let player_entity = self.ecs.fetch::<Entity>();
+                        intent
+                            .insert(
+                                *player_entity,
+                                WantsToUseItem {
+                                    item,
+                                    target: Some(target),
+                                },
+                            )
+                            .expect("não inseri a vontade de usar um item!");
+                        RunState::PlayerTurn
+                    }
+                }
+            }
+            RunState::ShowThrowItem => match gui::throw_item_menu(self, ctx) {
+                gui::ItemMenuResult::Cancel => RunState::AwaitingInput,
+                gui::ItemMenuResult::NoResponse => RunState::ShowThrowItem,
+                gui::ItemMenuResult::Selected((item_entity, _item_name)) => {
+                    RunState::ShowThrowTargeting {
+                        range: THROW_RANGE,
+                        item: item_entity,
+                        launcher: None,
+                    }
+                }
+                gui::ItemMenuResult::RangeSelected(_) => RunState::PlayerTurn,
+            },
+            RunState::ShowThrowTargeting {
+                range,
+                item,
+                launcher,
+            } => match gui::ranged_target(self, ctx, range, item) {
                 gui::ItemMenuResult::Cancel => RunState::AwaitingInput,
-                gui::ItemMenuResult::NoResponse => RunState::ShowTargeting { range, item },
+                gui::ItemMenuResult::NoResponse => RunState::ShowThrowTargeting {
+                    range,
+                    item,
+                    launcher,
+                },
                 gui::ItemMenuResult::Selected(_) => RunState::AwaitingInput,
                 gui::ItemMenuResult::RangeSelected(target) => {
-                    let mut intent = self.ecs.write_storage::<WantsToUseItem>();
+                    // só uma unidade da pilha sai voando
+                    let item = spawner::split_stack(&mut self.ecs, item);
+                    let mut intent = self.ecs.write_storage::<WantsToThrowItem>();
                     let player_entity = self.ecs.fetch::<Entity>();
                     intent
                         .insert(
                             *player_entity,
-                            WantsToUseItem {
+                            WantsToThrowItem {
                                 item,
-                                target: Some(target),
+                                target,
+                                launcher,
                             },
                         )
-                        .expect("não inseri a vontade de usar um item!");
+                        .expect("não tive vontade de arremessar nada...");
                     RunState::PlayerTurn
                 }
             },
+            RunState::LookMode => match gui::look_mode(self, ctx) {
+                gui::MenuResult::Cancel => RunState::AwaitingInput,
+                gui::MenuResult::NoResponse => RunState::LookMode,
+            },
+            RunState::ShowLog => match gui::log_viewer(self, ctx) {
+                gui::MenuResult::Cancel => RunState::AwaitingInput,
+                gui::MenuResult::NoResponse => RunState::ShowLog,
+            },
+            RunState::GameOver => match gui::game_over(self, ctx) {
+                gui::MenuResult::Cancel => {
+                    new_game(&mut self.ecs, None);
+                    RunState::MainMenu(MainMenuSelection::NewGame)
+                }
+                gui::MenuResult::NoResponse => RunState::GameOver,
+            },
+            RunState::ShowCharacterSheet => match gui::character_sheet(self, ctx) {
+                gui::MenuResult::Cancel => RunState::AwaitingInput,
+                gui::MenuResult::NoResponse => RunState::ShowCharacterSheet,
+            },
+            RunState::CharacterCreation => match gui::character_creation(self, ctx) {
+                gui::CharacterCreationResult::Cancel => {
+                    RunState::MainMenu(MainMenuSelection::NewGame)
+                }
+                gui::CharacterCreationResult::NoResponse => RunState::CharacterCreation,
+                gui::CharacterCreationResult::Done => {
+                    let (class, name) = {
+                        let draft = self.ecs.fetch::<gui::CharacterDraft>();
+                        (draft.class, draft.name.trim().to_string())
+                    };
+                    spawner::apply_class(&mut self.ecs, class, name);
+                    RunState::PreRun
+                }
+            },
             RunState::MainMenu(_) => match gui::main_menu(self, ctx) {
                 MainMenuResult::NoSelection(selection) => RunState::MainMenu(selection),
                 MainMenuResult::Selected(option) => match option {
-                    MainMenuSelection::NewGame => RunState::PreRun,
-                    MainMenuSelection::Load => RunState::PreRun,
+                    MainMenuSelection::NewGame => RunState::CharacterCreation,
+                    // ainda não há jogo salvo: carregar começa um jogo novo,
+                    // passando pela criação do personagem
+                    MainMenuSelection::Load => RunState::CharacterCreation,
+                    MainMenuSelection::Language => {
+                        // os nomes ficam guardados como IDs, então basta trocar o
+                        // catálogo; a partida continua como estava
+                        let language = self.ecs.fetch::<Catalogue>().language().next();
+                        let (catalogue, error) = Catalogue::load(language);
+                        self.ecs.insert(catalogue);
+                        if let Some(error) = error {
+                            self.ecs
+                                .fetch_mut::<GameLog>()
+                                .entry(LogCategory::System, "game.language_error")
+                                .arg("error", error)
+                                .log();
+                        }
+                        RunState::MainMenu(MainMenuSelection::Language)
+                    }
+                    MainMenuSelection::Settings => RunState::Settings,
                     MainMenuSelection::Quit => ::std::process::exit(0),
                 },
             },
+            RunState::Settings => match gui::settings_menu(self, ctx) {
+                gui::MenuResult::Cancel => RunState::MainMenu(MainMenuSelection::Settings),
+                gui::MenuResult::NoResponse => RunState::Settings,
+            },
         };
 
+        // o cursor começa no inimigo mais próximo ao mirar e no jogador ao olhar
+        let previous = *self.ecs.fetch::<RunState>();
+        if previous != newrunstate {
+            match newrunstate {
+                RunState::ShowTargeting { .. } | RunState::ShowThrowTargeting { .. } => {
+                    gui::reset_cursor(&self.ecs, true, ctx.mouse_pos())
+                }
+                RunState::LookMode => gui::reset_cursor(&self.ecs, false, ctx.mouse_pos()),
+                RunState::ShowLog => *self.ecs.write_resource() = gui::LogViewer::default(),
+                _ => {}
+            }
+        }
+
         *self.ecs.write_resource() = newrunstate;
 
         damage_system::delete_the_dead(&mut self.ecs);
@@ -194,56 +516,137 @@ impl GameState for State {
 }
 
 fn main() -> rltk::BError {
-    let mut context = RltkBuilder::simple80x50()
-        .with_title("Olá mundo!")
+    rltk::link_resource!(TILE_FONT_IMAGE, "resources/tiles.png");
+    let (catalogue, language_error) = Catalogue::load(Language::Portuguese);
+    // blocos por baixo, texto por cima: o console de texto é esparso para
+    // deixar os blocos aparecerem
+    let mut context = RltkBuilder::new()
+        .with_dimensions(CONSOLE_WIDTH, CONSOLE_HEIGHT)
+        .with_tile_dimensions(8, 8)
+        .with_title(catalogue.text("game.title"))
+        .with_font("terminal8x8.png", 8, 8)
+        .with_font(tileset::TILE_FONT, 16, 16)
+        .with_simple_console(CONSOLE_WIDTH, CONSOLE_HEIGHT, tileset::TILE_FONT)
+        .with_sparse_console(CONSOLE_WIDTH, CONSOLE_HEIGHT, "terminal8x8.png")
         .build()?;
     context.with_post_scanlines(true);
     let mut gs = State { ecs: World::new() };
     gs.ecs
         .insert(RunState::MainMenu(MainMenuSelection::NewGame));
     gs.ecs.insert(rltk::RandomNumberGenerator::new());
+    gs.ecs.insert(RenderMode::Ascii);
+    gs.ecs.insert(catalogue);
+    gs.ecs.insert(gui::CharacterDraft {
+        class: CharacterClass::Warrior,
+        name: String::new(),
+    });
+
+    register_components(&mut gs.ecs);
+
+    new_game(&mut gs.ecs, language_error);
+
+    rltk::main_loop(context, gs)?;
+
+    Ok(())
+}
+
+/// Registra todos os componentes; os testes montam o mundo com ela também.
+fn register_components(ecs: &mut World) {
+    ecs.register::<Position>();
+    ecs.register::<Renderable>();
+    ecs.register::<Player>();
+    ecs.register::<Viewshed>();
+    ecs.register::<Monster>();
+    ecs.register::<Name>();
+    ecs.register::<BlocksTile>();
+    ecs.register::<CombatStats>();
+    ecs.register::<NaturalAttack>();
+    ecs.register::<Attributes>();
+    ecs.register::<Skills>();
+    ecs.register::<Progression>();
+    ecs.register::<CharacterClass>();
+    ecs.register::<Mana>();
+    ecs.register::<Spell>();
+    ecs.register::<KnownSpells>();
+    ecs.register::<Charges>();
+    ecs.register::<Recharges>();
+    ecs.register::<ObfuscatedName>();
+    ecs.register::<Identifies>();
+    ecs.register::<Cursed>();
+    ecs.register::<RemovesCurse>();
+    ecs.register::<WantsToMelee>();
+    ecs.register::<SufferDamage>();
+    ecs.register::<Item>();
+    ecs.register::<ItemKind>();
+    ecs.register::<ProvidesHealing>();
+    ecs.register::<InBackpack>();
+    ecs.register::<WantsToPickupItem>();
+    ecs.register::<WantsToUseItem>();
+    ecs.register::<WantsToDropItem>();
+    ecs.register::<Consumable>();
+    ecs.register::<Stackable>();
+    ecs.register::<Weight>();
+    ecs.register::<Burden>();
+    ecs.register::<Weapon>();
+    ecs.register::<Ammunition>();
+    ecs.register::<Launcher>();
+    ecs.register::<WantsToThrowItem>();
+    ecs.register::<ParticleLifetime>();
+    ecs.register::<InflictsDamage>();
+    ecs.register::<Ranged>();
+    ecs.register::<AreaOfEffect>();
+    ecs.register::<Confusion>();
+    ecs.register::<Faction>();
+    ecs.register::<Companion>();
+    ecs.register::<Awareness>();
+}
 
-    gs.ecs.register::<Position>();
-    gs.ecs.register::<Renderable>();
-    gs.ecs.register::<Player>();
-    gs.ecs.register::<Viewshed>();
-    gs.ecs.register::<Monster>();
-    gs.ecs.register::<Name>();
-    gs.ecs.register::<BlocksTile>();
-    gs.ecs.register::<CombatStats>();
-    gs.ecs.register::<WantsToMelee>();
-    gs.ecs.register::<SufferDamage>();
-    gs.ecs.register::<Item>();
-    gs.ecs.register::<ProvidesHealing>();
-    gs.ecs.register::<InBackpack>();
-    gs.ecs.register::<WantsToPickupItem>();
-    gs.ecs.register::<WantsToUseItem>();
-    gs.ecs.register::<WantsToDropItem>();
-    gs.ecs.register::<Consumable>();
-    gs.ecs.register::<InflictsDamage>();
-    gs.ecs.register::<Ranged>();
-    gs.ecs.register::<AreaOfEffect>();
-    gs.ecs.register::<Confusion>();
-
-    let map = new_map();
+/// Cria o mapa, o jogador e os monstros do zero, jogando fora o que houvesse
+/// antes, inclusive os recursos que guardam o estado da partida.
+/// `language_error` é o problema que houver ao ler o catálogo de mensagens,
+/// para avisar o jogador.
+fn new_game(ecs: &mut World, language_error: Option<String>) {
+    ecs.delete_all();
+    ecs.insert(identification::ItemKnowledge::default());
+    ecs.insert(PlayerNoise { level: 0 });
+    ecs.insert(ParticleBuilder::default());
+    ecs.insert(gui::Cursor::new());
+    ecs.insert(gui::LogViewer::default());
+    ecs.insert(travel::Travel::default());
+    ecs.insert(RegenerationClock { turns: 0 });
+    ecs.insert(Resting::default());
+    let (keymap, keymap_error) = keybindings::KeyMap::load();
+    ecs.insert(keymap);
+
+    let map = new_map(MAP_WIDTH, MAP_HEIGHT);
 
     let (x, y) = map.rooms[0].center();
 
-    let player = spawner::player(&mut gs.ecs, x, y);
-    gs.ecs.insert(player);
+    let player = spawner::player(ecs, x, y);
+    ecs.insert(player);
+    spawner::dog(ecs, x + 1, y);
 
     for room in map.rooms.iter().skip(1) {
-        // spawner::random_monster(&mut gs.ecs, x, y);
-        spawner::spawn_room(&mut gs.ecs, room);
+        // spawner::random_monster(ecs, x, y);
+        spawner::spawn_room(ecs, room);
     }
 
-    gs.ecs.insert(map);
-    gs.ecs.insert(Point::new(x, y));
-    gs.ecs.insert(GameLog {
-        entries: vec!["Bem-vindo, mortal!".to_string()],
-    });
-
-    rltk::main_loop(context, gs)?;
-
-    Ok(())
+    ecs.insert(map);
+    ecs.insert(Point::new(x, y));
+    let mut game_log = GameLog::new();
+    game_log.system("game.welcome");
+    if let Some(error) = language_error {
+        game_log
+            .entry(LogCategory::System, "game.language_error")
+            .arg("error", error)
+            .log();
+    }
+    if let Some(error) = keymap_error {
+        game_log
+            .entry(LogCategory::System, "keys.error.ignored")
+            .arg("path", keybindings::CONFIG_PATH)
+            .arg("error", error)
+            .log();
+    }
+    ecs.insert(game_log);
 }
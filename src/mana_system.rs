@@ -0,0 +1,23 @@
+use specs::prelude::*;
+
+use crate::components::Mana;
+use crate::RunState;
+
+/// Recupera um ponto de mana por turno.
+pub struct ManaRegenSystem {}
+
+impl<'a> System<'a> for ManaRegenSystem {
+    type SystemData = (ReadExpect<'a, RunState>, WriteStorage<'a, Mana>);
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (runstate, mut manas) = data;
+
+        if *runstate != RunState::MonsterTurn {
+            return;
+        }
+
+        for mana in (&mut manas).join() {
+            mana.current = i32::min(mana.max, mana.current + 1);
+        }
+    }
+}
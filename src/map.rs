@@ -10,8 +10,13 @@ use specs::World;
 use std::cmp::max;
 use std::cmp::min;
 
-pub const WIDTH: usize = 80;
-pub const HEIGHT: usize = 43;
+use crate::camera::Camera;
+use crate::tileset::RenderMode;
+use crate::tileset::ASCII_CONSOLE;
+
+/// Tamanho dos mapas gerados; maior que a tela, a câmera acompanha o jogador.
+pub const MAP_WIDTH: usize = 120;
+pub const MAP_HEIGHT: usize = 70;
 pub const MAX_ROOMS: usize = 30;
 pub const MIN_ROOM_SIZE: usize = 6;
 pub const MAX_ROOM_SIZE: usize = 10;
@@ -27,6 +32,8 @@ pub struct Map {
     pub width: usize,
     pub height: usize,
     pub rooms: Vec<Rect>,
+    /// Andar da masmorra; o primeiro é 1.
+    pub depth: i32,
     pub revealed_tiles: Vec<bool>,
     pub visible_tiles: Vec<bool>,
     pub blocked: Vec<bool>,
@@ -34,10 +41,11 @@ pub struct Map {
 }
 
 impl Map {
-    fn new(width: usize, height: usize) -> Self {
+    pub fn new(width: usize, height: usize) -> Self {
         Self {
             width,
             height,
+            depth: 1,
             tiles: vec![TileType::Wall; width * height],
             rooms: Vec::new(),
             revealed_tiles: vec![false; width * height],
@@ -50,20 +58,23 @@ impl Map {
     fn apply_room(&mut self, room: &Rect) {
         for x in room.x1 + 1..=room.x2 {
             for y in room.y1 + 1..=room.y2 {
-                self.tiles[xy_idx(x as i32, y as i32)] = TileType::Floor;
+                let idx = self.xy_idx(x, y);
+                self.tiles[idx] = TileType::Floor;
             }
         }
     }
 
     fn apply_horizontal_tunnel(&mut self, x1: usize, x2: usize, y: usize) {
         for x in min(x1, x2)..=max(x1, x2) {
-            self.tiles[xy_idx(x as i32, y as i32)] = TileType::Floor;
+            let idx = self.xy_idx(x, y);
+            self.tiles[idx] = TileType::Floor;
         }
     }
 
     fn apply_vertical_tunnel(&mut self, y1: usize, y2: usize, x: usize) {
         for y in min(y1, y2)..=max(y1, y2) {
-            self.tiles[xy_idx(x as i32, y as i32)] = TileType::Floor;
+            let idx = self.xy_idx(x, y);
+            self.tiles[idx] = TileType::Floor;
         }
     }
 
@@ -75,6 +86,10 @@ impl Map {
         !self.blocked[idx]
     }
 
+    pub fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && x < self.width as i32 && y < self.height as i32
+    }
+
     pub fn xy_idx(&self, x: usize, y: usize) -> usize {
         (y * self.width) + x
     }
@@ -94,6 +109,11 @@ impl Map {
     pub fn clear_content_index(&mut self) {
         self.tile_content.iter_mut().for_each(|t| t.clear());
     }
+
+    /// Salas são iluminadas; corredores ficam no escuro.
+    pub fn is_lit(&self, x: i32, y: i32) -> bool {
+        self.rooms.iter().any(|room| room.contains(x, y))
+    }
 }
 
 impl BaseMap for Map {
@@ -174,31 +194,25 @@ impl Rect {
         self.x1 <= other.x2 && other.x1 <= self.x2 && self.y1 <= other.y2 && other.y1 <= self.y2
     }
 
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x > self.x1 as i32 && x <= self.x2 as i32 && y > self.y1 as i32 && y <= self.y2 as i32
+    }
+
     pub fn center(&self) -> (usize, usize) {
         ((self.x1 + self.x2) / 2, (self.y1 + self.y2) / 2)
     }
 }
 
-pub fn xy_idx(x: i32, y: i32) -> usize {
-    (y as usize * WIDTH) + x as usize
-}
-
-pub fn idx_xy(idx: usize) -> (i32, i32) {
-    let y = idx / WIDTH;
-    let x = idx % WIDTH;
-    (x as i32, y as i32)
-}
-
-pub fn new_map() -> Map {
-    let mut map = Map::new(WIDTH, HEIGHT);
+pub fn new_map(width: usize, height: usize) -> Map {
+    let mut map = Map::new(width, height);
 
     let mut rng = rltk::RandomNumberGenerator::new();
 
     'room_loop: for _ in 0..MAX_ROOMS {
         let w = rng.range(MIN_ROOM_SIZE, MAX_ROOM_SIZE);
         let h = rng.range(MIN_ROOM_SIZE, MAX_ROOM_SIZE);
-        let x = rng.roll_dice(1, WIDTH as i32 - w as i32 - 1) - 1;
-        let y = rng.roll_dice(1, HEIGHT as i32 - h as i32 - 1) - 1;
+        let x = rng.roll_dice(1, width as i32 - w as i32 - 1) - 1;
+        let y = rng.roll_dice(1, height as i32 - h as i32 - 1) - 1;
 
         let room = Rect::new(x as usize, y as usize, h, w);
 
@@ -209,7 +223,7 @@ pub fn new_map() -> Map {
         }
 
         map.apply_room(&room);
-        if map.rooms.len() != 0 {
+        if !map.rooms.is_empty() {
             let (last_x, last_y) = map.rooms[map.rooms.len() - 1].center();
             let (new_x, new_y) = room.center();
 
@@ -228,57 +242,80 @@ pub fn new_map() -> Map {
     map
 }
 
+/// Desenha o pedaço do mapa que a câmera enxerga; fora do mapa fica em branco.
 pub fn draw_map(ecs: &World, ctx: &mut Rltk) {
     let map = ecs.fetch::<Map>();
-
-    for (i, tile) in map.tiles.iter().enumerate() {
-        let (x, y) = idx_xy(i);
-
-        if map.revealed_tiles[i] {
-            let glyph;
-            let mut fg;
-            match tile {
-                TileType::Floor => {
-                    glyph = rltk::to_cp437('.');
-                    fg = RGB::from_f32(0.5, 0.5, 0.5);
-                }
-                TileType::Wall => {
-                    glyph = rltk::to_cp437('#');
-                    fg = RGB::from_f32(0., 1., 0.);
-                }
+    let camera = Camera::from_ecs(ecs);
+    let mode = *ecs.fetch::<RenderMode>();
+    let tileset = mode.tileset();
+
+    let view = camera.view;
+    for sy in view.y..view.y + view.height {
+        for sx in view.x..view.x + view.width {
+            let world = camera.origin + Point::new(sx - view.x, sy - view.y);
+            if !map.in_bounds(world.x, world.y) {
+                ctx.set(
+                    sx,
+                    sy,
+                    RGB::named(rltk::BLACK),
+                    RGB::named(rltk::BLACK),
+                    rltk::to_cp437(' '),
+                );
+                continue;
+            }
+            let i = map.xy_idx(world.x as usize, world.y as usize);
+            if !map.revealed_tiles[i] {
+                continue;
             }
+
+            let glyph = tileset.tile_glyph(&map, world.x as usize, world.y as usize);
+            // os blocos já têm cor; só escurecem quando saem de vista
+            let mut fg = match (mode, map.tiles[i]) {
+                (RenderMode::Tiles, _) => RGB::named(rltk::WHITE),
+                (_, TileType::Floor) => RGB::from_f32(0.5, 0.5, 0.5),
+                (_, TileType::Wall) => RGB::from_f32(0., 1., 0.),
+            };
             if !map.visible_tiles[i] {
-                fg = fg.to_greyscale()
+                fg = match mode {
+                    RenderMode::Tiles => RGB::from_f32(0.5, 0.5, 0.5),
+                    RenderMode::Ascii | RenderMode::AsciiLines => fg.to_greyscale(),
+                };
             }
-            ctx.set(x, y, fg, RGB::from_f32(0., 0., 0.), glyph);
+            ctx.set_active_console(tileset.console);
+            ctx.set(sx, sy, fg, RGB::from_f32(0., 0., 0.), glyph);
+            ctx.set_active_console(ASCII_CONSOLE);
         }
     }
 }
 
 #[allow(dead_code)]
-pub fn new_map_test() -> Vec<TileType> {
-    let mut map = vec![TileType::Floor; WIDTH * HEIGHT];
+pub fn new_map_test(width: usize, height: usize) -> Map {
+    let mut map = Map::new(width, height);
+    map.tiles = vec![TileType::Floor; width * height];
 
     // bordas topo
-    for x in 0..WIDTH as i32 {
-        map[xy_idx(x, 0)] = TileType::Wall;
-        map[xy_idx(x, HEIGHT as i32 - 1)] = TileType::Wall;
+    for x in 0..width {
+        let (top, bottom) = (map.xy_idx(x, 0), map.xy_idx(x, height - 1));
+        map.tiles[top] = TileType::Wall;
+        map.tiles[bottom] = TileType::Wall;
     }
 
     // bordas laterais
-    for y in 0..HEIGHT as i32 {
-        map[xy_idx(0, y)] = TileType::Wall;
-        map[xy_idx(WIDTH as i32 - 1, y)] = TileType::Wall;
+    for y in 0..height {
+        let (left, right) = (map.xy_idx(0, y), map.xy_idx(width - 1, y));
+        map.tiles[left] = TileType::Wall;
+        map.tiles[right] = TileType::Wall;
     }
 
     let mut rng = rltk::RandomNumberGenerator::new();
+    let center = map.xy_idx(width / 2, height / 2);
 
     for _ in 0..400 {
-        let x = rng.roll_dice(1, WIDTH as i32 - 1);
-        let y = rng.roll_dice(1, HEIGHT as i32 - 1);
-        let idx = xy_idx(x, y);
-        if idx != xy_idx(40, 25) {
-            map[idx] = TileType::Wall;
+        let x = rng.roll_dice(1, width as i32 - 1);
+        let y = rng.roll_dice(1, height as i32 - 1);
+        let idx = map.xy_idx(x as usize, y as usize);
+        if idx != center {
+            map.tiles[idx] = TileType::Wall;
         }
     }
 
@@ -293,21 +330,25 @@ mod tests {
     use quickcheck::Gen;
     use rand::Rng;
 
+    /// Um mapa de tamanho qualquer e uma célula dentro dele.
     #[derive(Clone, Debug)]
-    struct W(i32);
-
-    #[derive(Clone, Debug)]
-    struct H(i32);
-
-    impl Arbitrary for W {
-        fn arbitrary<G: Gen>(g: &mut G) -> Self {
-            W(g.gen_range(0, WIDTH as i32 - 1))
-        }
+    struct Cell {
+        width: usize,
+        height: usize,
+        x: usize,
+        y: usize,
     }
 
-    impl Arbitrary for H {
+    impl Arbitrary for Cell {
         fn arbitrary<G: Gen>(g: &mut G) -> Self {
-            H(g.gen_range(0, HEIGHT as i32 - 1))
+            let width = g.gen_range(1, 200);
+            let height = g.gen_range(1, 200);
+            Cell {
+                width,
+                height,
+                x: g.gen_range(0, width),
+                y: g.gen_range(0, height),
+            }
         }
     }
 
@@ -328,10 +369,9 @@ mod tests {
     }
 
     #[quickcheck]
-    fn xy_idx_id(x: W, y: H) -> bool {
-        let W(x) = x;
-        let H(y) = y;
-        idx_xy(xy_idx(x, y)) == (x, y)
+    fn xy_idx_id(cell: Cell) -> bool {
+        let map = Map::new(cell.width, cell.height);
+        map.idx_xy(map.xy_idx(cell.x, cell.y)) == (cell.x, cell.y)
     }
 
     #[quickcheck]
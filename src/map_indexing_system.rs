@@ -1,6 +1,7 @@
 use specs::prelude::*;
 
 use crate::components::BlocksTile;
+use crate::components::ParticleLifetime;
 use crate::components::Position;
 use crate::map::Map;
 
@@ -11,19 +12,21 @@ impl<'a> System<'a> for MapIndexingSystem {
         WriteExpect<'a, Map>,
         ReadStorage<'a, Position>,
         ReadStorage<'a, BlocksTile>,
+        ReadStorage<'a, ParticleLifetime>,
         Entities<'a>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mut map, positions, blockers, entities) = data;
+        let (mut map, positions, blockers, particles, entities) = data;
 
         map.populate_blocked();
         map.clear_content_index();
 
-        for (pos, entity) in (&positions, &entities).join() {
+        // partículas são só enfeite, não ocupam o mapa
+        for (pos, entity, ()) in (&positions, &entities, !&particles).join() {
             let idx = map.xy_idx(pos.x as usize, pos.y as usize);
 
-            if let Some(_) = blockers.get(entity) {
+            if blockers.get(entity).is_some() {
                 map.blocked[idx] = true;
             }
 
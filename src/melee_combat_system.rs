@@ -1,50 +1,211 @@
 use std::cmp::max;
 
+use rltk::DiceType;
+use rltk::Point;
+use rltk::RandomNumberGenerator;
+use rltk::RGB;
 use specs::prelude::*;
 
+use crate::attributes;
 use crate::components::*;
 use crate::game_log::GameLog;
+use crate::game_log::LogCategory;
+use crate::particle_system::ParticleBuilder;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AttackOutcome {
+    Fumble,
+    Miss,
+    Hit,
+    Critical,
+}
+
+/// Um 1 natural no d20 é sempre uma falha crítica e um 20 natural é sempre
+/// um acerto crítico; fora isso, o dado mais o bônus precisa alcançar a
+/// classe de armadura do alvo.
+pub fn attack_outcome(natural_roll: i32, hit_bonus: i32, armour_class: i32) -> AttackOutcome {
+    match natural_roll {
+        1 => AttackOutcome::Fumble,
+        20 => AttackOutcome::Critical,
+        roll if roll + hit_bonus >= armour_class => AttackOutcome::Hit,
+        _ => AttackOutcome::Miss,
+    }
+}
 
 pub struct MeleeCombatSystem {}
 
 impl<'a> System<'a> for MeleeCombatSystem {
     type SystemData = (
         Entities<'a>,
+        ReadExpect<'a, Entity>,
         WriteExpect<'a, GameLog>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        WriteExpect<'a, ParticleBuilder>,
         WriteStorage<'a, WantsToMelee>,
         ReadStorage<'a, Name>,
+        ReadStorage<'a, Renderable>,
         WriteStorage<'a, SufferDamage>,
         ReadStorage<'a, CombatStats>,
+        ReadStorage<'a, NaturalAttack>,
+        ReadStorage<'a, Attributes>,
+        ReadStorage<'a, Skills>,
+        ReadStorage<'a, Awareness>,
+        ReadStorage<'a, Burden>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Viewshed>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (entities, mut game_log, mut wants_to_melees, names, mut suffer_damages, combat_stats) =
-            data;
+        let (
+            entities,
+            player_entity,
+            mut game_log,
+            mut rng,
+            mut particle_builder,
+            mut wants_to_melees,
+            names,
+            renderables,
+            mut suffer_damages,
+            combat_stats,
+            natural_attacks,
+            attributes,
+            skills,
+            awarenesses,
+            burdens,
+            positions,
+            viewsheds,
+        ) = data;
 
-        for (_entity, wants_to_melee, name, stats) in
-            (&entities, &wants_to_melees, &names, &combat_stats).join()
+        // só entra no registro a luta que o jogador consegue ver
+        let player_sees = |entity: Entity| {
+            entity == *player_entity
+                || match (positions.get(entity), viewsheds.get(*player_entity)) {
+                    (Some(pos), Some(viewshed)) => {
+                        viewshed.visible_tiles.contains(&Point::new(pos.x, pos.y))
+                    }
+                    _ => false,
+                }
+        };
+
+        for (entity, wants_to_melee, name, stats, attack) in (
+            &entities,
+            &wants_to_melees,
+            &names,
+            &combat_stats,
+            &natural_attacks,
+        )
+            .join()
         {
             if stats.hp > 0 {
                 let target_stats = combat_stats.get(wants_to_melee.target).unwrap();
                 if target_stats.hp > 0 {
                     let target_name = names.get(wants_to_melee.target).unwrap();
-                    let damage = max(0, stats.power - target_stats.defense);
+                    let renderable = renderables.get(entity);
+                    let target_renderable = renderables.get(wants_to_melee.target);
+                    let seen = player_sees(entity) || player_sees(wants_to_melee.target);
+                    let asleep = awarenesses.get(wants_to_melee.target) == Some(&Awareness::Asleep);
+
+                    let (hit_bonus, damage_bonus) =
+                        match (attributes.get(entity), skills.get(entity)) {
+                            (Some(attributes), Some(skills)) => (
+                                attack.hit_bonus + attributes::hit_bonus(attributes, skills),
+                                attributes::damage_bonus(attributes),
+                            ),
+                            _ => (attack.hit_bonus, 0),
+                        };
+                    let hit_bonus = hit_bonus
+                        - burdens.get(entity).map_or(0, |b| {
+                            attributes::encumbrance_hit_penalty(attributes::encumbrance(
+                                b.load, b.capacity,
+                            ))
+                        });
+
+                    let natural_roll = rng.roll_dice(1, 20);
+                    let mut outcome =
+                        attack_outcome(natural_roll, hit_bonus, target_stats.armour_class);
+                    // quem dorme não se defende
+                    if asleep && outcome == AttackOutcome::Miss {
+                        outcome = AttackOutcome::Hit;
+                    }
+
+                    let mut damage = match outcome {
+                        AttackOutcome::Fumble => {
+                            if seen {
+                                game_log
+                                    .entry(LogCategory::Combat, "melee.fumble")
+                                    .name("attacker", &name.name, renderable)
+                                    .name("target", &target_name.name, target_renderable)
+                                    .log();
+                            }
+                            continue;
+                        }
+                        AttackOutcome::Miss => {
+                            if seen {
+                                game_log
+                                    .entry(LogCategory::Combat, "melee.miss")
+                                    .name("attacker", &name.name, renderable)
+                                    .name("target", &target_name.name, target_renderable)
+                                    .log();
+                            }
+                            continue;
+                        }
+                        AttackOutcome::Hit => rng.roll(attack.damage),
+                        AttackOutcome::Critical => rng.roll(DiceType {
+                            n_dice: attack.damage.n_dice * 2,
+                            ..attack.damage
+                        }),
+                    };
+                    damage = max(0, damage + damage_bonus);
+
+                    if asleep {
+                        damage *= 2;
+                        if seen {
+                            game_log
+                                .entry(LogCategory::Combat, "melee.sneak_attack")
+                                .name("attacker", &name.name, renderable)
+                                .name("target", &target_name.name, target_renderable)
+                                .log();
+                        }
+                    }
 
                     if damage == 0 {
-                        game_log.entries.push(format!(
-                            "{} não faz nem um arranhão em {}...",
-                            name.name, target_name.name
-                        ));
+                        if seen {
+                            game_log
+                                .entry(LogCategory::Combat, "melee.no_damage")
+                                .name("attacker", &name.name, renderable)
+                                .name("target", &target_name.name, target_renderable)
+                                .log();
+                        }
                     } else {
                         SufferDamage::new_damage(
                             &mut suffer_damages,
                             wants_to_melee.target,
-                            damage,
+                            damage as u32,
+                            entity == *player_entity,
                         );
-                        game_log.entries.push(format!(
-                            "{} sabuga {} causando {} de dano!",
-                            name.name, target_name.name, damage
-                        ));
+                        particle_builder.request_on(
+                            wants_to_melee.target,
+                            if outcome == AttackOutcome::Critical {
+                                RGB::named(rltk::RED)
+                            } else {
+                                RGB::named(rltk::ORANGE)
+                            },
+                            rltk::to_cp437('‼'),
+                            200.0,
+                        );
+                        let id = if outcome == AttackOutcome::Critical {
+                            "melee.critical"
+                        } else {
+                            "melee.hit"
+                        };
+                        if seen {
+                            game_log
+                                .entry(LogCategory::Combat, id)
+                                .name("attacker", &name.name, renderable)
+                                .name("target", &target_name.name, target_renderable)
+                                .damage("damage", damage)
+                                .log();
+                        }
                     }
                 }
             }
@@ -53,3 +214,23 @@ impl<'a> System<'a> for MeleeCombatSystem {
         wants_to_melees.clear();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn natural_rolls_ignore_armour() {
+        assert_eq!(attack_outcome(1, 100, 10), AttackOutcome::Fumble);
+        assert_eq!(attack_outcome(20, -100, 30), AttackOutcome::Critical);
+    }
+
+    #[quickcheck]
+    fn hits_when_roll_reaches_armour_class(roll: u8, hit_bonus: i8, armour_class: u8) -> bool {
+        let roll = 2 + (roll % 18) as i32;
+        let hit_bonus = hit_bonus as i32;
+        let armour_class = armour_class as i32;
+        let outcome = attack_outcome(roll, hit_bonus, armour_class);
+        (outcome == AttackOutcome::Hit) == (roll + hit_bonus >= armour_class)
+    }
+}
@@ -1,7 +1,10 @@
+use rltk::BaseMap;
 use rltk::Point;
 use specs::prelude::*;
 
 use crate::components::*;
+use crate::faction::reaction;
+use crate::faction::Reaction;
 use crate::map::Map;
 use crate::RunState;
 
@@ -10,11 +13,11 @@ pub struct MonsterAISystem {}
 impl<'a> System<'a> for MonsterAISystem {
     type SystemData = (
         Entities<'a>,
-        ReadExpect<'a, Map>,
-        ReadExpect<'a, Point>,
-        ReadExpect<'a, Entity>,
+        WriteExpect<'a, Map>,
         ReadExpect<'a, RunState>,
         ReadStorage<'a, Monster>,
+        ReadStorage<'a, Faction>,
+        ReadStorage<'a, Awareness>,
         WriteStorage<'a, Confusion>,
         WriteStorage<'a, Viewshed>,
         WriteStorage<'a, Position>,
@@ -24,11 +27,11 @@ impl<'a> System<'a> for MonsterAISystem {
     fn run(&mut self, data: Self::SystemData) {
         let (
             entities,
-            map,
-            player_pos,
-            player_entity,
+            mut map,
             runstate,
             monsters,
+            factions,
+            awarenesses,
             mut confusions,
             mut viewsheds,
             mut positions,
@@ -39,42 +42,123 @@ impl<'a> System<'a> for MonsterAISystem {
             return;
         };
 
-        for (entity, mut viewshed, _monster, mut pos) in
-            (&entities, &mut viewsheds, &monsters, &mut positions).join()
+        // todos que pertencem a alguma facção; as posições são atualizadas à
+        // medida que os monstros se movem
+        let mut others: Vec<(Entity, Faction, Point)> = (&entities, &factions, &positions)
+            .join()
+            .map(|(entity, faction, pos)| (entity, *faction, Point::new(pos.x, pos.y)))
+            .collect();
+
+        for (entity, viewshed, _monster, faction, pos, ()) in (
+            &entities,
+            &mut viewsheds,
+            &monsters,
+            &factions,
+            &mut positions,
+            !&awarenesses,
+        )
+            .join()
         {
             if let Some(confusion) = confusions.get_mut(entity) {
                 confusion.turns -= 1;
-                if confusion.turns <= 0 {
+                if confusion.turns == 0 {
                     confusions
                         .remove(entity)
                         .expect("não consegui desconfundir!");
                 };
-            } else {
-                let distance =
-                    rltk::DistanceAlg::Pythagoras.distance2d(Point::new(pos.x, pos.y), *player_pos);
+                continue;
+            }
+
+            let my_pos = Point::new(pos.x, pos.y);
+            let mut nearest_enemy: Option<(Entity, Point, f32)> = None;
+            let mut threats = Vec::new();
+            for (other, other_faction, other_pos) in others.iter() {
+                if *other == entity || !viewshed.visible_tiles.contains(other_pos) {
+                    continue;
+                }
+                let distance = rltk::DistanceAlg::Pythagoras.distance2d(my_pos, *other_pos);
+                match reaction(*faction, *other_faction) {
+                    Reaction::Attack => match nearest_enemy {
+                        Some((_, _, nearest)) if nearest <= distance => {}
+                        _ => nearest_enemy = Some((*other, *other_pos, distance)),
+                    },
+                    Reaction::Flee => threats.push(*other_pos),
+                    Reaction::Ignore | Reaction::Follow => {}
+                }
+            }
+
+            let destination = if let Some((target, target_pos, distance)) = nearest_enemy {
                 if distance < 1.5 {
                     wants_to_melees
-                        .insert(
-                            entity,
-                            WantsToMelee {
-                                target: *player_entity,
-                            },
-                        )
+                        .insert(entity, WantsToMelee { target })
                         .expect("não consegui criar a vontade de matar!");
-                } else if viewshed.visible_tiles.contains(&*player_pos) {
-                    let path = rltk::a_star_search(
-                        map.xy_idx(pos.x as usize, pos.y as usize),
-                        map.xy_idx(player_pos.x as usize, player_pos.y as usize),
-                        &*map,
-                    );
-                    if path.success && path.steps.len() > 1 {
-                        let (step_x, step_y) = map.idx_xy(path.steps[1]);
-                        pos.x = step_x as i32;
-                        pos.y = step_y as i32;
-                        viewshed.dirty = true;
-                    }
+                    None
+                } else {
+                    step_towards(&mut map, my_pos, target_pos)
+                }
+            } else if !threats.is_empty() {
+                step_away(&map, my_pos, &threats)
+            } else {
+                None
+            };
+
+            if let Some(destination) = destination {
+                let old_idx = map.xy_idx(pos.x as usize, pos.y as usize);
+                let new_idx = map.xy_idx(destination.x as usize, destination.y as usize);
+                map.blocked[old_idx] = false;
+                map.blocked[new_idx] = true;
+                pos.x = destination.x;
+                pos.y = destination.y;
+                viewshed.dirty = true;
+                if let Some(me) = others.iter_mut().find(|(other, _, _)| *other == entity) {
+                    me.2 = destination;
                 }
             }
         }
     }
 }
+
+/// Próximo passo no caminho até `to`. O destino é desbloqueado durante a
+/// busca porque normalmente está ocupado pelo próprio alvo.
+pub fn step_towards(map: &mut Map, from: Point, to: Point) -> Option<Point> {
+    let start = map.xy_idx(from.x as usize, from.y as usize);
+    let end = map.xy_idx(to.x as usize, to.y as usize);
+    let was_blocked = map.blocked[end];
+    map.blocked[end] = false;
+    let path = rltk::a_star_search(start, end, &*map);
+    map.blocked[end] = was_blocked;
+
+    if path.success && path.steps.len() > 1 && path.steps[1] != end {
+        let (x, y) = map.idx_xy(path.steps[1]);
+        Some(Point::new(x, y))
+    } else {
+        None
+    }
+}
+
+/// Vizinho livre que mais aumenta a distância até a ameaça mais próxima.
+fn step_away(map: &Map, from: Point, threats: &[Point]) -> Option<Point> {
+    let closest_threat = |p: Point| {
+        threats
+            .iter()
+            .map(|t| rltk::DistanceAlg::Pythagoras.distance2d(p, *t))
+            .fold(f32::MAX, f32::min)
+    };
+
+    let start = map.xy_idx(from.x as usize, from.y as usize);
+    let mut best = (from, closest_threat(from));
+    for (idx, _cost) in map.get_available_exits(start).iter() {
+        let (x, y) = map.idx_xy(*idx);
+        let candidate = Point::new(x, y);
+        let distance = closest_threat(candidate);
+        if distance > best.1 {
+            best = (candidate, distance);
+        }
+    }
+
+    if best.0 == from {
+        None
+    } else {
+        Some(best.0)
+    }
+}
@@ -0,0 +1,186 @@
+use rltk::FontCharType;
+use rltk::LineAlg;
+use rltk::Point;
+use rltk::RGB;
+use specs::prelude::*;
+
+use crate::components::ParticleLifetime;
+use crate::components::Position;
+use crate::components::Renderable;
+
+/// Quanto tempo cada passo do rastro de um projétil fica na tela.
+const PROJECTILE_STEP_MS: f32 = 40.0;
+
+struct ParticleRequest {
+    x: i32,
+    y: i32,
+    fg: RGB,
+    bg: RGB,
+    glyph: FontCharType,
+    lifetime_ms: f32,
+}
+
+struct AttachedRequest {
+    entity: Entity,
+    fg: RGB,
+    glyph: FontCharType,
+    lifetime_ms: f32,
+}
+
+struct ProjectileRequest {
+    from: Entity,
+    to: Point,
+    fg: RGB,
+    glyph: FontCharType,
+}
+
+/// Pedidos de partículas feitos pelos sistemas durante o turno; o
+/// `ParticleSpawnSystem` transforma tudo em entidades no fim da rodada.
+#[derive(Default)]
+pub struct ParticleBuilder {
+    requests: Vec<ParticleRequest>,
+    attached: Vec<AttachedRequest>,
+    projectiles: Vec<ProjectileRequest>,
+}
+
+impl ParticleBuilder {
+    pub fn request(
+        &mut self,
+        x: i32,
+        y: i32,
+        fg: RGB,
+        bg: RGB,
+        glyph: FontCharType,
+        lifetime_ms: f32,
+    ) {
+        self.requests.push(ParticleRequest {
+            x,
+            y,
+            fg,
+            bg,
+            glyph,
+            lifetime_ms,
+        });
+    }
+
+    /// Partícula em cima de onde `entity` estiver quando o turno terminar.
+    pub fn request_on(&mut self, entity: Entity, fg: RGB, glyph: FontCharType, lifetime_ms: f32) {
+        self.attached.push(AttachedRequest {
+            entity,
+            fg,
+            glyph,
+            lifetime_ms,
+        });
+    }
+
+    /// Rastro que sai da posição de `from` até `to`, sumindo do começo para o
+    /// fim para parecer que o projétil voa.
+    pub fn projectile(&mut self, from: Entity, to: Point, fg: RGB, glyph: FontCharType) {
+        self.projectiles.push(ProjectileRequest {
+            from,
+            to,
+            fg,
+            glyph,
+        });
+    }
+}
+
+pub struct ParticleSpawnSystem {}
+
+impl<'a> System<'a> for ParticleSpawnSystem {
+    type SystemData = (
+        Entities<'a>,
+        WriteExpect<'a, ParticleBuilder>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, Renderable>,
+        WriteStorage<'a, ParticleLifetime>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut particle_builder, mut positions, mut renderables, mut lifetimes) = data;
+
+        let attached = particle_builder.attached.drain(..).collect::<Vec<_>>();
+        for request in attached {
+            if let Some(pos) = positions.get(request.entity) {
+                particle_builder.request(
+                    pos.x,
+                    pos.y,
+                    request.fg,
+                    RGB::named(rltk::BLACK),
+                    request.glyph,
+                    request.lifetime_ms,
+                );
+            }
+        }
+
+        let projectiles = particle_builder.projectiles.drain(..).collect::<Vec<_>>();
+        for projectile in projectiles {
+            let from = match positions.get(projectile.from) {
+                Some(pos) => Point::new(pos.x, pos.y),
+                None => continue,
+            };
+            let path = rltk::line2d(LineAlg::Bresenham, from, projectile.to);
+            for (step, point) in path.iter().filter(|p| **p != from).enumerate() {
+                particle_builder.request(
+                    point.x,
+                    point.y,
+                    projectile.fg,
+                    RGB::named(rltk::BLACK),
+                    projectile.glyph,
+                    PROJECTILE_STEP_MS * (step + 1) as f32,
+                );
+            }
+        }
+
+        for request in particle_builder.requests.drain(..) {
+            let particle = entities.create();
+            positions
+                .insert(
+                    particle,
+                    Position {
+                        x: request.x,
+                        y: request.y,
+                    },
+                )
+                .expect("não consegui posicionar a partícula!");
+            renderables
+                .insert(
+                    particle,
+                    Renderable {
+                        glyph: request.glyph,
+                        fg: request.fg,
+                        bg: request.bg,
+                        render_order: 0,
+                    },
+                )
+                .expect("não consegui desenhar a partícula!");
+            lifetimes
+                .insert(
+                    particle,
+                    ParticleLifetime {
+                        lifetime_ms: request.lifetime_ms,
+                    },
+                )
+                .expect("não consegui dar vida à partícula!");
+        }
+    }
+}
+
+/// Remove as partículas cujo tempo acabou; roda a cada quadro, independente
+/// do turno, para que os efeitos nunca segurem a entrada do jogador.
+pub fn cull_dead_particles(ecs: &mut World, frame_time_ms: f32) {
+    let dead = {
+        let entities = ecs.entities();
+        let mut lifetimes = ecs.write_storage::<ParticleLifetime>();
+        let mut dead = Vec::new();
+        for (entity, lifetime) in (&entities, &mut lifetimes).join() {
+            lifetime.lifetime_ms -= frame_time_ms;
+            if lifetime.lifetime_ms < 0.0 {
+                dead.push(entity);
+            }
+        }
+        dead
+    };
+    ecs.delete_entities(&dead)
+        .expect("não consegui apagar as partículas!");
+}
@@ -0,0 +1,135 @@
+use rltk::Point;
+use rltk::RandomNumberGenerator;
+use specs::prelude::*;
+
+use crate::components::*;
+use crate::game_log::GameLog;
+use crate::game_log::LogCategory;
+use crate::map::Map;
+use crate::RunState;
+
+pub const WALK_NOISE: i32 = 1;
+pub const MELEE_NOISE: i32 = 8;
+
+/// Barulho feito pelo jogador no último turno.
+pub struct PlayerNoise {
+    pub level: i32,
+}
+
+pub struct PerceptionSystem {}
+
+impl<'a> System<'a> for PerceptionSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Map>,
+        ReadExpect<'a, Point>,
+        ReadExpect<'a, RunState>,
+        WriteExpect<'a, PlayerNoise>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        WriteExpect<'a, GameLog>,
+        ReadStorage<'a, Name>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Viewshed>,
+        WriteStorage<'a, Awareness>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            map,
+            player_pos,
+            runstate,
+            mut noise,
+            mut rng,
+            mut game_log,
+            names,
+            positions,
+            viewsheds,
+            mut awarenesses,
+        ) = data;
+
+        if *runstate != RunState::MonsterTurn {
+            return;
+        }
+
+        let lit = map.is_lit(player_pos.x, player_pos.y);
+        let mut alerted = Vec::new();
+        for (entity, awareness, pos, viewshed) in
+            (&entities, &awarenesses, &positions, &viewsheds).join()
+        {
+            let distance =
+                rltk::DistanceAlg::Pythagoras.distance2d(Point::new(pos.x, pos.y), *player_pos);
+            let sees_player = viewshed.visible_tiles.contains(&*player_pos);
+            let roll = rng.roll_dice(1, 20);
+            if notices(*awareness, roll, distance, noise.level, sees_player, lit) {
+                alerted.push(entity);
+                let idx = map.xy_idx(pos.x as usize, pos.y as usize);
+                if let (true, Some(name)) = (map.visible_tiles[idx], names.get(entity)) {
+                    let id = match awareness {
+                        Awareness::Asleep => "perception.wakes_up",
+                        Awareness::Unaware => "perception.notices",
+                    };
+                    game_log
+                        .entry(LogCategory::Combat, id)
+                        .arg("name", &name.name)
+                        .log();
+                }
+            }
+        }
+
+        for entity in alerted.iter() {
+            awarenesses.remove(*entity);
+        }
+
+        noise.level = 0;
+    }
+}
+
+/// Teste de percepção: o dado mais o barulho (e a visão do jogador, se o
+/// monstro estiver acordado) precisa vencer uma dificuldade que cresce com a
+/// distância.
+pub fn notices(
+    awareness: Awareness,
+    roll: i32,
+    distance: f32,
+    noise: i32,
+    sees_player: bool,
+    lit: bool,
+) -> bool {
+    let distance = distance as i32;
+    match awareness {
+        Awareness::Asleep => roll + noise >= 20 + distance,
+        Awareness::Unaware => {
+            let sight = match (sees_player, lit) {
+                (false, _) => 0,
+                (true, false) => 2,
+                (true, true) => 6,
+            };
+            roll + noise + sight >= 12 + distance
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sleepers_ignore_quiet_footsteps_from_afar() {
+        assert!(!notices(Awareness::Asleep, 20, 5.0, WALK_NOISE, true, true));
+        assert!(notices(
+            Awareness::Asleep,
+            20,
+            5.0,
+            MELEE_NOISE,
+            false,
+            false
+        ));
+    }
+
+    #[test]
+    fn light_makes_the_player_easier_to_spot() {
+        assert!(!notices(Awareness::Unaware, 10, 3.0, 0, true, false));
+        assert!(notices(Awareness::Unaware, 10, 3.0, 0, true, true));
+    }
+}
@@ -1,12 +1,26 @@
 use rltk::Point;
+use rltk::RandomNumberGenerator;
 use rltk::Rltk;
-use rltk::VirtualKeyCode;
 use specs::prelude::*;
 use std::cmp::max;
 use std::cmp::min;
 
+use crate::camera::Camera;
 use crate::components::*;
+use crate::faction::reaction;
+use crate::faction::Reaction;
+use crate::game_log::GameLog;
+use crate::game_log::LogCategory;
+use crate::gui::visible_hostiles;
+use crate::keybindings::Action;
+use crate::keybindings::KeyContext;
+use crate::keybindings::KeyMap;
 use crate::map::*;
+use crate::perception_system::PlayerNoise;
+use crate::perception_system::MELEE_NOISE;
+use crate::perception_system::WALK_NOISE;
+use crate::travel;
+use crate::travel::Travel;
 use crate::RunState;
 use crate::State;
 
@@ -16,67 +30,203 @@ pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
     let mut wants_to_melee = ecs.write_storage::<WantsToMelee>();
     let mut players = ecs.write_storage::<Player>();
     let mut ppos = ecs.write_resource::<Point>();
+    let mut game_log = ecs.fetch_mut::<GameLog>();
+    let mut noise = ecs.fetch_mut::<PlayerNoise>();
+    let mut rng = ecs.fetch_mut::<RandomNumberGenerator>();
+    let mut confusions = ecs.write_storage::<Confusion>();
     let combat_stats = ecs.read_storage::<CombatStats>();
+    let factions = ecs.read_storage::<Faction>();
+    let names = ecs.read_storage::<Name>();
     let entities = ecs.entities();
     let map = ecs.fetch::<Map>();
 
+    let mut swapped = None;
     for (pos, _player, viewshed, entity) in
         (&mut positions, &mut players, &mut viewsheds, &entities).join()
     {
+        // confuso, o jogador cambaleia para qualquer lado
+        let (delta_x, delta_y) = match confusions.get_mut(entity) {
+            Some(confusion) => {
+                confusion.turns -= 1;
+                if confusion.turns == 0 {
+                    confusions.remove(entity);
+                    game_log.system("confusion.recovered");
+                } else {
+                    game_log.system("confusion.stagger");
+                }
+                (rng.range(-1, 2), rng.range(-1, 2))
+            }
+            None => (delta_x, delta_y),
+        };
+
         let dest_idx = map.xy_idx((pos.x + delta_x) as usize, (pos.y + delta_y) as usize);
 
         for potential_target in map.tile_content[dest_idx].iter() {
-            if let Some(_) = combat_stats.get(*potential_target) {
-                wants_to_melee
-                    .insert(
-                        entity,
-                        WantsToMelee {
-                            target: *potential_target,
-                        },
-                    )
-                    .expect("não consegui inserir a vontade de tretar!");
-                return;
+            if combat_stats.get(*potential_target).is_some() {
+                let player_reaction = match (factions.get(entity), factions.get(*potential_target))
+                {
+                    (Some(mine), Some(theirs)) => reaction(*mine, *theirs),
+                    _ => Reaction::Attack,
+                };
+                if player_reaction == Reaction::Attack {
+                    wants_to_melee
+                        .insert(
+                            entity,
+                            WantsToMelee {
+                                target: *potential_target,
+                            },
+                        )
+                        .expect("não consegui inserir a vontade de tretar!");
+                    noise.level = MELEE_NOISE;
+                    return;
+                }
+                swapped = Some((*potential_target, pos.x, pos.y));
             }
         }
 
-        if !map.blocked[dest_idx] {
-            pos.x = min(WIDTH as i32 - 1, max(0, pos.x + delta_x));
-            pos.y = min(HEIGHT as i32 - 1, max(0, pos.y + delta_y));
+        if swapped.is_some() || !map.blocked[dest_idx] {
+            pos.x = min(map.width as i32 - 1, max(0, pos.x + delta_x));
+            pos.y = min(map.height as i32 - 1, max(0, pos.y + delta_y));
             ppos.x = pos.x;
             ppos.y = pos.y;
             viewshed.dirty = true;
+            noise.level = WALK_NOISE;
+        }
+    }
+
+    // quem não é hostil troca de lugar com o jogador em vez de apanhar
+    if let Some((other, x, y)) = swapped {
+        if let Some(other_pos) = positions.get_mut(other) {
+            other_pos.x = x;
+            other_pos.y = y;
         }
+        if let Some(other_viewshed) = viewsheds.get_mut(other) {
+            other_viewshed.dirty = true;
+        }
+        if let Some(name) = names.get(other) {
+            game_log
+                .entry(LogCategory::System, "move.swap")
+                .arg("name", &name.name)
+                .log();
+        }
+    }
+}
+
+/// Descanso automático até a vida encher; `last_hp` serve para notar dano.
+#[derive(Default)]
+pub struct Resting {
+    active: bool,
+    last_hp: i32,
+}
+
+pub fn player_hp(ecs: &World) -> (i32, i32) {
+    let player_entity = ecs.fetch::<Entity>();
+    ecs.read_storage::<CombatStats>()
+        .get(*player_entity)
+        .map_or((0, 0), |stats| (stats.hp, stats.max_hp as i32))
+}
+
+fn start_resting(ecs: &World) -> RunState {
+    let (hp, max_hp) = player_hp(ecs);
+    if hp >= max_hp {
+        ecs.fetch_mut::<GameLog>().system("rest.already_rested");
+        return RunState::AwaitingInput;
     }
+    let mut resting = ecs.fetch_mut::<Resting>();
+    resting.active = true;
+    resting.last_hp = hp;
+    RunState::AwaitingInput
+}
+
+/// Passa mais um turno descansando, a menos que algo interrompa.
+fn rest_step(ecs: &World) -> RunState {
+    let (hp, max_hp) = player_hp(ecs);
+    let interruption = if !visible_hostiles(ecs).is_empty() {
+        Some("rest.hostiles_near")
+    } else if hp < ecs.fetch::<Resting>().last_hp {
+        Some("rest.interrupted")
+    } else if hp >= max_hp {
+        Some("rest.done")
+    } else {
+        None
+    };
+
+    let mut resting = ecs.fetch_mut::<Resting>();
+    if let Some(message) = interruption {
+        resting.active = false;
+        ecs.fetch_mut::<GameLog>().system(message);
+        return RunState::AwaitingInput;
+    }
+    resting.last_hp = hp;
+    RunState::PlayerTurn
 }
 
 pub fn player_input(gs: &mut State, ctx: &Rltk) -> RunState {
-    match ctx.key {
+    if gs.ecs.fetch::<Resting>().active {
+        if ctx.key.is_none() && !ctx.left_click {
+            return rest_step(&gs.ecs);
+        }
+        gs.ecs.fetch_mut::<Resting>().active = false;
+        return RunState::AwaitingInput;
+    }
+
+    // qualquer tecla ou clique interrompe a viagem automática
+    if gs.ecs.fetch::<Travel>().is_active() {
+        if ctx.key.is_none() && !ctx.left_click {
+            return travel::travel_step(&mut gs.ecs);
+        }
+        gs.ecs.fetch_mut::<Travel>().stop();
+        return RunState::AwaitingInput;
+    }
+
+    if ctx.left_click {
+        return match Camera::from_ecs(&gs.ecs).to_world(ctx.mouse_pos()) {
+            Some(target) => travel::travel_to(&gs.ecs, target),
+            None => RunState::AwaitingInput,
+        };
+    }
+
+    let action = match gs
+        .ecs
+        .fetch::<KeyMap>()
+        .action(KeyContext::Gameplay, ctx.key)
+    {
         None => return RunState::AwaitingInput,
-        Some(key) => match key {
-            VirtualKeyCode::Left | VirtualKeyCode::H => try_move_player(-1, 0, &mut gs.ecs),
+        Some(action) => action,
+    };
 
-            VirtualKeyCode::Right | VirtualKeyCode::L => try_move_player(1, 0, &mut gs.ecs),
+    if let Some((dx, dy)) = action.direction() {
+        try_move_player(dx, dy, &mut gs.ecs);
+        return RunState::PlayerTurn;
+    }
 
-            VirtualKeyCode::Up | VirtualKeyCode::K => try_move_player(0, -1, &mut gs.ecs),
+    match action {
+        Action::PickUp => get_item(&mut gs.ecs),
 
-            VirtualKeyCode::Down | VirtualKeyCode::J => try_move_player(0, 1, &mut gs.ecs),
+        Action::Inventory => return RunState::ShowInventory,
 
-            VirtualKeyCode::U => try_move_player(1, -1, &mut gs.ecs),
+        Action::Drop => return RunState::ShowDropItem,
 
-            VirtualKeyCode::Y => try_move_player(-1, -1, &mut gs.ecs),
+        Action::Throw => return RunState::ShowThrowItem,
 
-            VirtualKeyCode::N => try_move_player(1, 1, &mut gs.ecs),
+        Action::Companions => command_companions(&mut gs.ecs),
 
-            VirtualKeyCode::B => try_move_player(-1, 1, &mut gs.ecs),
+        Action::CharacterSheet => return RunState::ShowCharacterSheet,
 
-            VirtualKeyCode::G => get_item(&mut gs.ecs),
+        Action::Spells => return RunState::ShowSpells,
 
-            VirtualKeyCode::I => return RunState::ShowInventory,
+        Action::Look => return RunState::LookMode,
 
-            VirtualKeyCode::D => return RunState::ShowDropItem,
+        Action::MessageLog => return RunState::ShowLog,
 
-            _ => return RunState::AwaitingInput,
-        },
+        Action::Explore => return travel::explore(&gs.ecs),
+
+        // esperar um turno
+        Action::Wait => {}
+
+        Action::Rest => return start_resting(&gs.ecs),
+
+        _ => return RunState::AwaitingInput,
     }
     RunState::PlayerTurn
 }
@@ -90,7 +240,7 @@ fn get_item(ecs: &mut World) {
     let idx = map.xy_idx(player_pos.x as usize, player_pos.y as usize);
 
     for potential_item in map.tile_content[idx].iter() {
-        if let Some(_) = items.get(*potential_item) {
+        if items.get(*potential_item).is_some() {
             wants_to_pickup_items
                 .insert(
                     *player_entity,
@@ -103,3 +253,27 @@ fn get_item(ecs: &mut World) {
         }
     }
 }
+
+fn command_companions(ecs: &mut World) {
+    let mut companions = ecs.write_storage::<Companion>();
+    let mut game_log = ecs.fetch_mut::<GameLog>();
+
+    let order = match (&companions).join().next() {
+        Some(Companion {
+            order: CompanionOrder::Follow,
+        }) => CompanionOrder::Stay,
+        Some(Companion {
+            order: CompanionOrder::Stay,
+        }) => CompanionOrder::Follow,
+        None => return,
+    };
+
+    for companion in (&mut companions).join() {
+        companion.order = order;
+    }
+
+    game_log.system(match order {
+        CompanionOrder::Follow => "companions.follow",
+        CompanionOrder::Stay => "companions.stay",
+    });
+}
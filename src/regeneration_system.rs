@@ -0,0 +1,71 @@
+use specs::prelude::*;
+
+use crate::attributes;
+use crate::components::*;
+use crate::faction::reaction;
+use crate::faction::Reaction;
+use crate::RunState;
+
+/// Turnos passados em paz desde o último ponto de vida recuperado.
+pub struct RegenerationClock {
+    pub turns: i32,
+}
+
+/// O jogador recupera um ponto de vida de tempos em tempos, mas só quando
+/// não há nenhum inimigo à vista.
+pub struct RegenerationSystem {}
+
+impl<'a> System<'a> for RegenerationSystem {
+    type SystemData = (
+        ReadExpect<'a, Entity>,
+        ReadExpect<'a, RunState>,
+        WriteExpect<'a, RegenerationClock>,
+        ReadStorage<'a, Viewshed>,
+        ReadStorage<'a, Position>,
+        ReadStorage<'a, Faction>,
+        ReadStorage<'a, Attributes>,
+        WriteStorage<'a, CombatStats>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            player_entity,
+            runstate,
+            mut clock,
+            viewsheds,
+            positions,
+            factions,
+            attributes,
+            mut combat_stats,
+        ) = data;
+
+        if *runstate != RunState::MonsterTurn {
+            return;
+        }
+
+        let hostile_in_sight = match viewsheds.get(*player_entity) {
+            Some(viewshed) => (&positions, &factions).join().any(|(pos, faction)| {
+                reaction(Faction::Player, *faction) == Reaction::Attack
+                    && viewshed
+                        .visible_tiles
+                        .contains(&rltk::Point::new(pos.x, pos.y))
+            }),
+            None => false,
+        };
+        if hostile_in_sight {
+            clock.turns = 0;
+            return;
+        }
+
+        let interval = attributes
+            .get(*player_entity)
+            .map_or(attributes::BASE_REGEN_INTERVAL, attributes::regen_interval);
+        clock.turns += 1;
+        if clock.turns >= interval {
+            clock.turns = 0;
+            if let Some(stats) = combat_stats.get_mut(*player_entity) {
+                stats.hp = i32::min(stats.max_hp as i32, stats.hp + 1);
+            }
+        }
+    }
+}
@@ -1,15 +1,48 @@
+use rltk::DiceType;
 use rltk::RandomNumberGenerator;
 use rltk::RGB;
 use specs::prelude::*;
 
+use crate::attributes;
 use crate::components::*;
+use crate::i18n::Text;
+use crate::identification::ItemCategory;
+use crate::identification::ItemKnowledge;
 use crate::map::Rect;
 
 const MAX_MONSTERS: usize = 4;
 const MAX_ITEMS: usize = 2;
 
+trait WithAttributes {
+    /// Atributos e perícias junto com as estatísticas de combate derivadas
+    /// deles para uma criatura de nível 1.
+    fn with_attributes(self, attributes: Attributes, skills: Skills) -> Self;
+}
+
+impl WithAttributes for EntityBuilder<'_> {
+    fn with_attributes(self, attributes: Attributes, skills: Skills) -> Self {
+        self.with(attributes::combat_stats(&attributes, &skills, 1))
+            .with(attributes)
+            .with(skills)
+    }
+}
+
 pub fn player(ecs: &mut World, x: usize, y: usize) -> Entity {
-    ecs.create_entity()
+    let attributes = Attributes {
+        might: 12,
+        fitness: 15,
+        quickness: 12,
+        intelligence: 10,
+    };
+    let skills = Skills {
+        melee: 2,
+        defense: 1,
+        magic: 0,
+    };
+    let max_mana = attributes::max_mana(&attributes, &skills, 1);
+
+    let player = ecs
+        .create_entity()
         .with(Position {
             x: x as i32,
             y: y as i32,
@@ -27,69 +60,236 @@ pub fn player(ecs: &mut World, x: usize, y: usize) -> Entity {
             dirty: true,
         })
         .with(Name {
-            name: "Player".to_string(),
+            name: Text::message("creature.player"),
         })
-        .with(CombatStats {
-            max_hp: 30,
-            hp: 30,
-            defense: 2,
-            power: 5,
+        .with(Faction::Player)
+        .with_attributes(attributes, skills)
+        .with(Progression { level: 1, xp: 0 })
+        .with(Mana {
+            current: max_mana,
+            max: max_mana,
         })
-        .build()
+        .with(NaturalAttack {
+            hit_bonus: 0,
+            damage: DiceType::new(1, 6, 1),
+        })
+        .build();
+
+    let magic_missile = magic_missile_spell(ecs);
+    ecs.write_storage::<KnownSpells>()
+        .insert(
+            player,
+            KnownSpells {
+                spells: vec![magic_missile],
+            },
+        )
+        .expect("não consegui ensinar magias ao jogador!");
+
+    player
 }
 
 pub fn random_monster(ecs: &mut World, x: usize, y: usize) {
     let roll = {
         let mut rng = ecs.fetch_mut::<RandomNumberGenerator>();
-        rng.rand()
+        rng.roll_dice(1, 5)
     };
 
-    if roll {
-        goblin(ecs, x, y);
-    } else {
-        orc(ecs, x, y);
+    match roll {
+        1 | 2 => goblin(ecs, x, y),
+        3 | 4 => orc(ecs, x, y),
+        _ => rat(ecs, x, y),
     }
 }
 
 fn goblin(ecs: &mut World, x: usize, y: usize) {
-    monster(ecs, x, y, rltk::to_cp437('g'), "Goblin");
+    monster(
+        ecs,
+        x,
+        y,
+        rltk::to_cp437('g'),
+        RGB::named(rltk::RED),
+        Text::message("creature.goblin"),
+        Faction::Goblins,
+    )
+    .with_attributes(
+        Attributes {
+            might: 10,
+            fitness: 6,
+            quickness: 12,
+            intelligence: 8,
+        },
+        Skills {
+            melee: 2,
+            defense: 0,
+            magic: 0,
+        },
+    )
+    .with(NaturalAttack {
+        hit_bonus: 0,
+        damage: DiceType::new(1, 4, 1),
+    })
+    .build();
 }
 
 fn orc(ecs: &mut World, x: usize, y: usize) {
-    monster(ecs, x, y, rltk::to_cp437('o'), "Orc");
+    monster(
+        ecs,
+        x,
+        y,
+        rltk::to_cp437('o'),
+        RGB::named(rltk::RED),
+        Text::message("creature.orc"),
+        Faction::Orcs,
+    )
+    .with_attributes(
+        Attributes {
+            might: 12,
+            fitness: 8,
+            quickness: 10,
+            intelligence: 6,
+        },
+        Skills {
+            melee: 2,
+            defense: 3,
+            magic: 0,
+        },
+    )
+    .with(NaturalAttack {
+        hit_bonus: 0,
+        damage: DiceType::new(1, 8, 0),
+    })
+    .build();
 }
 
-fn monster<S: ToString>(ecs: &mut World, x: usize, y: usize, glyph: rltk::FontCharType, name: S) {
+fn rat(ecs: &mut World, x: usize, y: usize) {
     ecs.create_entity()
         .with(Position {
             x: x as i32,
             y: y as i32,
         })
         .with(Renderable {
-            glyph,
-            fg: RGB::named(rltk::RED),
+            glyph: rltk::to_cp437('r'),
+            fg: RGB::named(rltk::BURLYWOOD),
             bg: RGB::named(rltk::BLACK),
             render_order: 1,
         })
         .with(Viewshed {
-            range: 8,
+            range: 6,
             visible_tiles: Vec::new(),
             dirty: true,
         })
         .with(Monster {})
         .with(Name {
-            name: name.to_string(),
+            name: Text::message("creature.rat"),
         })
+        .with(Faction::Critters)
         .with(BlocksTile {})
-        .with(CombatStats {
-            max_hp: 16,
-            hp: 16,
-            defense: 1,
-            power: 4,
+        .with_attributes(
+            Attributes {
+                might: 8,
+                fitness: 2,
+                quickness: 10,
+                intelligence: 2,
+            },
+            Skills {
+                melee: 1,
+                defense: 0,
+                magic: 0,
+            },
+        )
+        .with(NaturalAttack {
+            hit_bonus: 0,
+            damage: DiceType::new(1, 2, 0),
         })
         .build();
 }
 
+pub fn dog(ecs: &mut World, x: usize, y: usize) -> Entity {
+    ecs.create_entity()
+        .with(Position {
+            x: x as i32,
+            y: y as i32,
+        })
+        .with(Renderable {
+            glyph: rltk::to_cp437('d'),
+            fg: RGB::named(rltk::WHEAT),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 1,
+        })
+        .with(Viewshed {
+            range: 8,
+            visible_tiles: Vec::new(),
+            dirty: true,
+        })
+        .with(Companion {
+            order: CompanionOrder::Follow,
+        })
+        .with(Name {
+            name: Text::message("creature.dog"),
+        })
+        .with(Faction::Player)
+        .with(BlocksTile {})
+        .with_attributes(
+            Attributes {
+                might: 10,
+                fitness: 10,
+                quickness: 14,
+                intelligence: 4,
+            },
+            Skills {
+                melee: 2,
+                defense: 0,
+                magic: 0,
+            },
+        )
+        .with(NaturalAttack {
+            hit_bonus: 0,
+            damage: DiceType::new(1, 6, 0),
+        })
+        .build()
+}
+
+fn monster(
+    ecs: &mut World,
+    x: usize,
+    y: usize,
+    glyph: rltk::FontCharType,
+    fg: RGB,
+    name: Text,
+    faction: Faction,
+) -> EntityBuilder<'_> {
+    let awareness = {
+        let mut rng = ecs.fetch_mut::<RandomNumberGenerator>();
+        if rng.rand() {
+            Awareness::Asleep
+        } else {
+            Awareness::Unaware
+        }
+    };
+
+    ecs.create_entity()
+        .with(Position {
+            x: x as i32,
+            y: y as i32,
+        })
+        .with(Renderable {
+            glyph,
+            fg,
+            bg: RGB::named(rltk::BLACK),
+            render_order: 1,
+        })
+        .with(Viewshed {
+            range: 8,
+            visible_tiles: Vec::new(),
+            dirty: true,
+        })
+        .with(Monster {})
+        .with(Name { name })
+        .with(faction)
+        .with(awareness)
+        .with(BlocksTile {})
+}
+
 pub fn spawn_room(ecs: &mut World, room: &Rect) {
     let mut monster_spawn_points = Vec::new();
     let mut item_spawn_points = Vec::new();
@@ -147,6 +347,19 @@ pub fn random_item(ecs: &mut World, x: usize, y: usize) {
         magic_missile_scroll,
         fireball_scroll,
         confusion_scroll,
+        magic_missile_wand,
+        confusion_wand,
+        recharging_scroll,
+        identify_scroll,
+        remove_curse_scroll,
+        ice_cone_scroll,
+        earthquake_scroll,
+        lightning_wand,
+        dagger,
+        arrow,
+        bolt,
+        bow,
+        crossbow,
     ];
 
     let item_fn = {
@@ -154,10 +367,465 @@ pub fn random_item(ecs: &mut World, x: usize, y: usize) {
         rng.random_slice_entry(&items).unwrap()
     };
 
-    item_fn(ecs, x, y);
+    let item = item_fn(ecs, x, y);
+
+    // munição aparece em feixes
+    if ecs.read_storage::<Ammunition>().get(item).is_some() {
+        let quantity = {
+            let mut rng = ecs.fetch_mut::<RandomNumberGenerator>();
+            rng.roll_dice(2, 4) as u32 + 2
+        };
+        if let Some(stack) = ecs.write_storage::<Stackable>().get_mut(item) {
+            stack.quantity = quantity;
+        }
+    }
+
+    let cursed = {
+        let mut rng = ecs.fetch_mut::<RandomNumberGenerator>();
+        rng.roll_dice(1, 6) == 1
+    };
+    if cursed && ecs.read_storage::<RemovesCurse>().get(item).is_none() {
+        ecs.write_storage::<Cursed>()
+            .insert(item, Cursed { known: false })
+            .expect("não consegui amaldiçoar o item!");
+    }
+}
+
+pub fn magic_missile_wand(ecs: &mut World, x: usize, y: usize) -> Entity {
+    let max = {
+        let mut rng = ecs.fetch_mut::<RandomNumberGenerator>();
+        rng.roll_dice(1, 3) as u32 + 2
+    };
+
+    let obfuscated = obfuscated_name(ecs, "item.magic_missile_wand", ItemCategory::Wand);
+
+    ecs.create_entity()
+        .with(Position {
+            x: x as i32,
+            y: y as i32,
+        })
+        .with(Renderable {
+            glyph: rltk::to_cp437('/'),
+            fg: RGB::named(rltk::CYAN),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: Text::message("item.magic_missile_wand"),
+        })
+        .with(ItemKind {
+            id: "item.magic_missile_wand",
+        })
+        .with(obfuscated)
+        .with(Item {})
+        .with(Weight { kg: 1.0 })
+        .with(Charges { current: max, max })
+        .with(Ranged { range: 6 })
+        .with(InflictsDamage { damage: 8 })
+        .build()
+}
+
+pub fn confusion_wand(ecs: &mut World, x: usize, y: usize) -> Entity {
+    let max = {
+        let mut rng = ecs.fetch_mut::<RandomNumberGenerator>();
+        rng.roll_dice(1, 3) as u32 + 1
+    };
+
+    let obfuscated = obfuscated_name(ecs, "item.confusion_wand", ItemCategory::Wand);
+
+    ecs.create_entity()
+        .with(Position {
+            x: x as i32,
+            y: y as i32,
+        })
+        .with(Renderable {
+            glyph: rltk::to_cp437('/'),
+            fg: RGB::named(rltk::PINK),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: Text::message("item.confusion_wand"),
+        })
+        .with(ItemKind {
+            id: "item.confusion_wand",
+        })
+        .with(obfuscated)
+        .with(Item {})
+        .with(Weight { kg: 1.0 })
+        .with(Charges { current: max, max })
+        .with(Ranged { range: 6 })
+        .with(Confusion { turns: 4 })
+        .build()
+}
+
+pub fn recharging_scroll(ecs: &mut World, x: usize, y: usize) -> Entity {
+    let obfuscated = obfuscated_name(ecs, "item.recharging_scroll", ItemCategory::Scroll);
+
+    ecs.create_entity()
+        .with(Position {
+            x: x as i32,
+            y: y as i32,
+        })
+        .with(Renderable {
+            glyph: rltk::to_cp437(')'),
+            fg: RGB::named(rltk::GOLD),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: Text::message("item.recharging_scroll"),
+        })
+        .with(ItemKind {
+            id: "item.recharging_scroll",
+        })
+        .with(obfuscated)
+        .with(Item {})
+        .with(Weight { kg: 0.5 })
+        .with(Consumable {})
+        .with(Stackable { quantity: 1 })
+        .with(Recharges {})
+        .build()
+}
+
+pub fn identify_scroll(ecs: &mut World, x: usize, y: usize) -> Entity {
+    let obfuscated = obfuscated_name(ecs, "item.identify_scroll", ItemCategory::Scroll);
+
+    ecs.create_entity()
+        .with(Position {
+            x: x as i32,
+            y: y as i32,
+        })
+        .with(Renderable {
+            glyph: rltk::to_cp437(')'),
+            fg: RGB::named(rltk::WHITE),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: Text::message("item.identify_scroll"),
+        })
+        .with(ItemKind {
+            id: "item.identify_scroll",
+        })
+        .with(obfuscated)
+        .with(Item {})
+        .with(Weight { kg: 0.5 })
+        .with(Consumable {})
+        .with(Stackable { quantity: 1 })
+        .with(Identifies {})
+        .build()
 }
 
-pub fn health_potion(ecs: &mut World, x: usize, y: usize) {
+pub fn remove_curse_scroll(ecs: &mut World, x: usize, y: usize) -> Entity {
+    let obfuscated = obfuscated_name(ecs, "item.remove_curse_scroll", ItemCategory::Scroll);
+
+    ecs.create_entity()
+        .with(Position {
+            x: x as i32,
+            y: y as i32,
+        })
+        .with(Renderable {
+            glyph: rltk::to_cp437(')'),
+            fg: RGB::named(rltk::LIGHT_GREEN),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: Text::message("item.remove_curse_scroll"),
+        })
+        .with(ItemKind {
+            id: "item.remove_curse_scroll",
+        })
+        .with(obfuscated)
+        .with(Item {})
+        .with(Weight { kg: 0.5 })
+        .with(Consumable {})
+        .with(Stackable { quantity: 1 })
+        .with(RemovesCurse {})
+        .build()
+}
+
+pub fn magic_missile_spell(ecs: &mut World) -> Entity {
+    ecs.create_entity()
+        .with(Name {
+            name: Text::message("spell.magic_missile"),
+        })
+        .with(Spell { mana_cost: 4 })
+        .with(Ranged { range: 6 })
+        .with(InflictsDamage { damage: 8 })
+        .build()
+}
+
+pub fn fireball_spell(ecs: &mut World) -> Entity {
+    ecs.create_entity()
+        .with(Name {
+            name: Text::message("spell.fireball"),
+        })
+        .with(Spell { mana_cost: 12 })
+        .with(Ranged { range: 6 })
+        .with(InflictsDamage { damage: 20 })
+        .with(AreaOfEffect {
+            radius: 3,
+            shape: AreaShape::Burst,
+        })
+        .build()
+}
+
+pub fn confusion_spell(ecs: &mut World) -> Entity {
+    ecs.create_entity()
+        .with(Name {
+            name: Text::message("spell.confusion"),
+        })
+        .with(Spell { mana_cost: 6 })
+        .with(Ranged { range: 6 })
+        .with(Confusion { turns: 4 })
+        .build()
+}
+
+pub fn healing_spell(ecs: &mut World) -> Entity {
+    ecs.create_entity()
+        .with(Name {
+            name: Text::message("spell.healing"),
+        })
+        .with(Spell { mana_cost: 6 })
+        .with(ProvidesHealing { heal_amount: 8 })
+        .build()
+}
+
+fn obfuscated_name(ecs: &mut World, id: &'static str, category: ItemCategory) -> ObfuscatedName {
+    let mut rng = ecs.fetch_mut::<RandomNumberGenerator>();
+    let mut knowledge = ecs.fetch_mut::<ItemKnowledge>();
+    ObfuscatedName {
+        name: knowledge.appearance(&mut rng, ItemKind { id }, category),
+    }
+}
+
+/// Função que cria um item no mapa.
+type ItemSpawner = fn(&mut World, usize, usize) -> Entity;
+
+/// Cria o item direto na mochila de `owner`; o personagem já conhece o que
+/// traz de casa.
+fn give_item(ecs: &mut World, owner: Entity, item_fn: ItemSpawner) {
+    let item = item_fn(ecs, 0, 0);
+    ecs.write_storage::<Position>().remove(item);
+    let kind = *ecs.read_storage::<ItemKind>().get(item).unwrap();
+    ecs.fetch_mut::<ItemKnowledge>().identify(kind);
+
+    let stack = {
+        let entities = ecs.entities();
+        let backpacks = ecs.read_storage::<InBackpack>();
+        let kinds = ecs.read_storage::<ItemKind>();
+        let stackables = ecs.read_storage::<Stackable>();
+        if stackables.get(item).is_some() {
+            (&entities, &backpacks, &kinds, &stackables)
+                .join()
+                .find(|(_stack, backpack, stack_kind, _)| {
+                    backpack.owner == owner && **stack_kind == kind
+                })
+                .map(|(stack, ..)| stack)
+        } else {
+            None
+        }
+    };
+
+    if let Some(stack) = stack {
+        if let Some(stackable) = ecs.write_storage::<Stackable>().get_mut(stack) {
+            stackable.quantity += 1;
+        }
+        ecs.delete_entity(item)
+            .expect("não consegui empilhar o item inicial!");
+    } else {
+        ecs.write_storage::<InBackpack>()
+            .insert(item, InBackpack { owner })
+            .expect("não consegui colocar o item inicial na mochila!");
+    }
+}
+
+/// Spawner de cada item empilhável, pelo seu `ItemKind`, usado para separar
+/// uma unidade da pilha.
+const STACKABLE_ITEMS: [(&str, ItemSpawner); 12] = [
+    ("item.health_potion", health_potion),
+    ("item.magic_missile_scroll", magic_missile_scroll),
+    ("item.fireball_scroll", fireball_scroll),
+    ("item.confusion_scroll", confusion_scroll),
+    ("item.recharging_scroll", recharging_scroll),
+    ("item.identify_scroll", identify_scroll),
+    ("item.remove_curse_scroll", remove_curse_scroll),
+    ("item.ice_cone_scroll", ice_cone_scroll),
+    ("item.earthquake_scroll", earthquake_scroll),
+    ("item.dagger", dagger),
+    ("item.arrow", arrow),
+    ("item.bolt", bolt),
+];
+
+fn stackable_item_fn(kind: ItemKind) -> Option<ItemSpawner> {
+    STACKABLE_ITEMS
+        .iter()
+        .find(|(id, _item_fn)| *id == kind.id)
+        .map(|(_id, item_fn)| *item_fn)
+}
+
+/// Tira uma unidade de uma pilha na mochila e devolve a entidade dela, que
+/// continua na mochila do mesmo dono. Itens sozinhos e pilhas amaldiçoadas,
+/// que não se separam, são devolvidos como estão.
+pub fn split_stack(ecs: &mut World, stack: Entity) -> Entity {
+    let quantity = ecs
+        .read_storage::<Stackable>()
+        .get(stack)
+        .map_or(1, |s| s.quantity);
+    if ecs.read_storage::<Cursed>().get(stack).is_some() {
+        return stack;
+    }
+    let item_fn = ecs
+        .read_storage::<ItemKind>()
+        .get(stack)
+        .and_then(|kind| stackable_item_fn(*kind));
+    let (item_fn, owner) = match (item_fn, ecs.read_storage::<InBackpack>().get(stack)) {
+        (Some(item_fn), Some(backpack)) if quantity > 1 => (item_fn, backpack.owner),
+        _ => return stack,
+    };
+
+    let item = item_fn(ecs, 0, 0);
+    ecs.write_storage::<Position>().remove(item);
+    ecs.write_storage::<InBackpack>()
+        .insert(item, InBackpack { owner })
+        .expect("não consegui separar o item da pilha!");
+    if let Some(stackable) = ecs.write_storage::<Stackable>().get_mut(stack) {
+        stackable.quantity -= 1;
+    }
+    item
+}
+
+/// Atributos e perícias iniciais de cada classe.
+pub fn class_attributes(class: CharacterClass) -> (Attributes, Skills) {
+    match class {
+        CharacterClass::Warrior => (
+            Attributes {
+                might: 15,
+                fitness: 15,
+                quickness: 10,
+                intelligence: 8,
+            },
+            Skills {
+                melee: 3,
+                defense: 2,
+                magic: 0,
+            },
+        ),
+        CharacterClass::Mage => (
+            Attributes {
+                might: 8,
+                fitness: 11,
+                quickness: 11,
+                intelligence: 16,
+            },
+            Skills {
+                melee: 0,
+                defense: 0,
+                magic: 3,
+            },
+        ),
+        CharacterClass::Rogue => (
+            Attributes {
+                might: 11,
+                fitness: 12,
+                quickness: 15,
+                intelligence: 12,
+            },
+            Skills {
+                melee: 2,
+                defense: 1,
+                magic: 0,
+            },
+        ),
+    }
+}
+
+/// Transforma o jogador criado em `player` no personagem escolhido na tela
+/// de criação, com os atributos e os itens iniciais da classe.
+pub fn apply_class<S: ToString>(ecs: &mut World, class: CharacterClass, name: S) {
+    let player_entity = *ecs.fetch::<Entity>();
+    let (attributes, skills) = class_attributes(class);
+
+    {
+        let level = ecs
+            .read_storage::<Progression>()
+            .get(player_entity)
+            .map_or(1, |p| p.level);
+        ecs.write_storage::<CombatStats>()
+            .insert(
+                player_entity,
+                attributes::combat_stats(&attributes, &skills, level),
+            )
+            .expect("não consegui ajustar a vida do jogador!");
+        ecs.write_storage::<Attributes>()
+            .insert(player_entity, attributes)
+            .expect("não consegui ajustar os atributos do jogador!");
+        ecs.write_storage::<Skills>()
+            .insert(player_entity, skills)
+            .expect("não consegui ajustar as perícias do jogador!");
+        ecs.write_storage::<Name>()
+            .insert(
+                player_entity,
+                Name {
+                    name: Text::Literal(name.to_string()),
+                },
+            )
+            .expect("não consegui batizar o jogador!");
+        ecs.write_storage::<CharacterClass>()
+            .insert(player_entity, class)
+            .expect("não consegui definir a classe do jogador!");
+    }
+
+    let starting_spells: &[fn(&mut World) -> Entity] = match class {
+        CharacterClass::Warrior => &[],
+        CharacterClass::Mage => &[magic_missile_spell, healing_spell, fireball_spell],
+        CharacterClass::Rogue => &[confusion_spell],
+    };
+    let spells = starting_spells
+        .iter()
+        .map(|spell_fn| spell_fn(ecs))
+        .collect::<Vec<_>>();
+    let forgotten = ecs
+        .write_storage::<KnownSpells>()
+        .remove(player_entity)
+        .map_or(Vec::new(), |known| known.spells);
+    ecs.delete_entities(&forgotten)
+        .expect("não consegui esquecer as magias antigas!");
+    {
+        let level = ecs
+            .read_storage::<Progression>()
+            .get(player_entity)
+            .map_or(1, |p| p.level);
+        let max_mana = attributes::max_mana(&attributes, &skills, level);
+        ecs.write_storage::<Mana>()
+            .insert(
+                player_entity,
+                Mana {
+                    current: max_mana,
+                    max: max_mana,
+                },
+            )
+            .expect("não consegui ajustar a mana do jogador!");
+        ecs.write_storage::<KnownSpells>()
+            .insert(player_entity, KnownSpells { spells })
+            .expect("não consegui ensinar magias ao jogador!");
+    }
+
+    let starting_items: &[ItemSpawner] = match class {
+        CharacterClass::Warrior => &[health_potion, health_potion],
+        CharacterClass::Mage => &[magic_missile_scroll, magic_missile_scroll, confusion_scroll],
+        CharacterClass::Rogue => &[health_potion, confusion_scroll, dagger, dagger, dagger],
+    };
+    for item_fn in starting_items.iter() {
+        give_item(ecs, player_entity, *item_fn);
+    }
+}
+
+pub fn health_potion(ecs: &mut World, x: usize, y: usize) -> Entity {
+    let obfuscated = obfuscated_name(ecs, "item.health_potion", ItemCategory::Potion);
+
     ecs.create_entity()
         .with(Position {
             x: x as i32,
@@ -170,15 +838,23 @@ pub fn health_potion(ecs: &mut World, x: usize, y: usize) {
             render_order: 2,
         })
         .with(Item {})
+        .with(Weight { kg: 1.5 })
         .with(Name {
-            name: "Poção de Vida".to_string(),
+            name: Text::message("item.health_potion"),
         })
+        .with(ItemKind {
+            id: "item.health_potion",
+        })
+        .with(obfuscated)
         .with(ProvidesHealing { heal_amount: 8 })
         .with(Consumable {})
-        .build();
+        .with(Stackable { quantity: 1 })
+        .build()
 }
 
-pub fn magic_missile_scroll(ecs: &mut World, x: usize, y: usize) {
+pub fn magic_missile_scroll(ecs: &mut World, x: usize, y: usize) -> Entity {
+    let obfuscated = obfuscated_name(ecs, "item.magic_missile_scroll", ItemCategory::Scroll);
+
     ecs.create_entity()
         .with(Position {
             x: x as i32,
@@ -191,16 +867,24 @@ pub fn magic_missile_scroll(ecs: &mut World, x: usize, y: usize) {
             render_order: 2,
         })
         .with(Name {
-            name: "Pergaminho de Míssil Mágico".to_string(),
+            name: Text::message("item.magic_missile_scroll"),
+        })
+        .with(ItemKind {
+            id: "item.magic_missile_scroll",
         })
+        .with(obfuscated)
         .with(Item {})
+        .with(Weight { kg: 0.5 })
         .with(Consumable {})
+        .with(Stackable { quantity: 1 })
         .with(Ranged { range: 6 })
         .with(InflictsDamage { damage: 8 })
-        .build();
+        .build()
 }
 
-pub fn fireball_scroll(ecs: &mut World, x: usize, y: usize) {
+pub fn fireball_scroll(ecs: &mut World, x: usize, y: usize) -> Entity {
+    let obfuscated = obfuscated_name(ecs, "item.fireball_scroll", ItemCategory::Scroll);
+
     ecs.create_entity()
         .with(Position {
             x: x as i32,
@@ -213,17 +897,28 @@ pub fn fireball_scroll(ecs: &mut World, x: usize, y: usize) {
             render_order: 2,
         })
         .with(Name {
-            name: "Pergaminho de Bola de Fogo".to_string(),
+            name: Text::message("item.fireball_scroll"),
+        })
+        .with(ItemKind {
+            id: "item.fireball_scroll",
         })
+        .with(obfuscated)
         .with(Item {})
+        .with(Weight { kg: 0.5 })
         .with(Consumable {})
+        .with(Stackable { quantity: 1 })
         .with(Ranged { range: 6 })
         .with(InflictsDamage { damage: 20 })
-        .with(AreaOfEffect { radius: 3 })
-        .build();
+        .with(AreaOfEffect {
+            radius: 3,
+            shape: AreaShape::Burst,
+        })
+        .build()
 }
 
-pub fn confusion_scroll(ecs: &mut World, x: usize, y: usize) {
+pub fn confusion_scroll(ecs: &mut World, x: usize, y: usize) -> Entity {
+    let obfuscated = obfuscated_name(ecs, "item.confusion_scroll", ItemCategory::Scroll);
+
     ecs.create_entity()
         .with(Position {
             x: x as i32,
@@ -236,11 +931,299 @@ pub fn confusion_scroll(ecs: &mut World, x: usize, y: usize) {
             render_order: 2,
         })
         .with(Name {
-            name: "Pergaminho de Confusão".to_string(),
+            name: Text::message("item.confusion_scroll"),
         })
+        .with(ItemKind {
+            id: "item.confusion_scroll",
+        })
+        .with(obfuscated)
         .with(Item {})
+        .with(Weight { kg: 0.5 })
         .with(Consumable {})
+        .with(Stackable { quantity: 1 })
         .with(Confusion { turns: 4 })
         .with(Ranged { range: 6 })
-        .build();
+        .build()
+}
+
+pub fn dagger(ecs: &mut World, x: usize, y: usize) -> Entity {
+    ecs.create_entity()
+        .with(Position {
+            x: x as i32,
+            y: y as i32,
+        })
+        .with(Renderable {
+            glyph: rltk::to_cp437('|'),
+            fg: RGB::named(rltk::LIGHT_GRAY),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: Text::message("item.dagger"),
+        })
+        .with(ItemKind { id: "item.dagger" })
+        .with(Item {})
+        .with(Weight { kg: 1.0 })
+        .with(Weapon {
+            hit_bonus: 1,
+            damage: DiceType {
+                n_dice: 1,
+                die_type: 4,
+                bonus: 0,
+            },
+        })
+        .with(Stackable { quantity: 1 })
+        .build()
+}
+
+pub fn arrow(ecs: &mut World, x: usize, y: usize) -> Entity {
+    ecs.create_entity()
+        .with(Position {
+            x: x as i32,
+            y: y as i32,
+        })
+        .with(Renderable {
+            glyph: rltk::to_cp437('-'),
+            fg: RGB::named(rltk::BURLYWOOD),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: Text::message("item.arrow"),
+        })
+        .with(ItemKind { id: "item.arrow" })
+        .with(Item {})
+        .with(Weight { kg: 0.1 })
+        .with(Weapon {
+            hit_bonus: 0,
+            damage: DiceType {
+                n_dice: 1,
+                die_type: 6,
+                bonus: 0,
+            },
+        })
+        .with(Ammunition {
+            kind: AmmoKind::Arrow,
+        })
+        .with(Stackable { quantity: 1 })
+        .build()
+}
+
+pub fn bolt(ecs: &mut World, x: usize, y: usize) -> Entity {
+    ecs.create_entity()
+        .with(Position {
+            x: x as i32,
+            y: y as i32,
+        })
+        .with(Renderable {
+            glyph: rltk::to_cp437('-'),
+            fg: RGB::named(rltk::GRAY),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: Text::message("item.bolt"),
+        })
+        .with(ItemKind { id: "item.bolt" })
+        .with(Item {})
+        .with(Weight { kg: 0.15 })
+        .with(Weapon {
+            hit_bonus: 0,
+            damage: DiceType {
+                n_dice: 1,
+                die_type: 8,
+                bonus: 0,
+            },
+        })
+        .with(Ammunition {
+            kind: AmmoKind::Bolt,
+        })
+        .with(Stackable { quantity: 1 })
+        .build()
+}
+
+pub fn bow(ecs: &mut World, x: usize, y: usize) -> Entity {
+    ecs.create_entity()
+        .with(Position {
+            x: x as i32,
+            y: y as i32,
+        })
+        .with(Renderable {
+            glyph: rltk::to_cp437('}'),
+            fg: RGB::named(rltk::BURLYWOOD),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: Text::message("item.bow"),
+        })
+        .with(ItemKind { id: "item.bow" })
+        .with(Item {})
+        .with(Weight { kg: 2.0 })
+        .with(Launcher {
+            ammo: AmmoKind::Arrow,
+            range: 8,
+            hit_bonus: 0,
+        })
+        .build()
+}
+
+pub fn crossbow(ecs: &mut World, x: usize, y: usize) -> Entity {
+    ecs.create_entity()
+        .with(Position {
+            x: x as i32,
+            y: y as i32,
+        })
+        .with(Renderable {
+            glyph: rltk::to_cp437('}'),
+            fg: RGB::named(rltk::GRAY),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: Text::message("item.crossbow"),
+        })
+        .with(ItemKind {
+            id: "item.crossbow",
+        })
+        .with(Item {})
+        .with(Weight { kg: 4.0 })
+        .with(Launcher {
+            ammo: AmmoKind::Bolt,
+            range: 10,
+            hit_bonus: 2,
+        })
+        .build()
+}
+
+pub fn ice_cone_scroll(ecs: &mut World, x: usize, y: usize) -> Entity {
+    let obfuscated = obfuscated_name(ecs, "item.ice_cone_scroll", ItemCategory::Scroll);
+
+    ecs.create_entity()
+        .with(Position {
+            x: x as i32,
+            y: y as i32,
+        })
+        .with(Renderable {
+            glyph: rltk::to_cp437(')'),
+            fg: RGB::named(rltk::LIGHT_BLUE),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: Text::message("item.ice_cone_scroll"),
+        })
+        .with(ItemKind {
+            id: "item.ice_cone_scroll",
+        })
+        .with(obfuscated)
+        .with(Item {})
+        .with(Weight { kg: 0.5 })
+        .with(Consumable {})
+        .with(Stackable { quantity: 1 })
+        .with(Ranged { range: 6 })
+        .with(InflictsDamage { damage: 12 })
+        .with(AreaOfEffect {
+            radius: 5,
+            shape: AreaShape::Cone,
+        })
+        .build()
+}
+
+pub fn earthquake_scroll(ecs: &mut World, x: usize, y: usize) -> Entity {
+    let obfuscated = obfuscated_name(ecs, "item.earthquake_scroll", ItemCategory::Scroll);
+
+    ecs.create_entity()
+        .with(Position {
+            x: x as i32,
+            y: y as i32,
+        })
+        .with(Renderable {
+            glyph: rltk::to_cp437(')'),
+            fg: RGB::named(rltk::BROWN1),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: Text::message("item.earthquake_scroll"),
+        })
+        .with(ItemKind {
+            id: "item.earthquake_scroll",
+        })
+        .with(obfuscated)
+        .with(Item {})
+        .with(Weight { kg: 0.5 })
+        .with(Consumable {})
+        .with(Stackable { quantity: 1 })
+        .with(Ranged { range: 6 })
+        .with(InflictsDamage { damage: 10 })
+        .with(AreaOfEffect {
+            radius: 1,
+            shape: AreaShape::Square,
+        })
+        .build()
+}
+
+pub fn lightning_wand(ecs: &mut World, x: usize, y: usize) -> Entity {
+    let max = {
+        let mut rng = ecs.fetch_mut::<RandomNumberGenerator>();
+        rng.roll_dice(1, 3) as u32 + 1
+    };
+
+    let obfuscated = obfuscated_name(ecs, "item.lightning_wand", ItemCategory::Wand);
+
+    ecs.create_entity()
+        .with(Position {
+            x: x as i32,
+            y: y as i32,
+        })
+        .with(Renderable {
+            glyph: rltk::to_cp437('/'),
+            fg: RGB::named(rltk::YELLOW),
+            bg: RGB::named(rltk::BLACK),
+            render_order: 2,
+        })
+        .with(Name {
+            name: Text::message("item.lightning_wand"),
+        })
+        .with(ItemKind {
+            id: "item.lightning_wand",
+        })
+        .with(obfuscated)
+        .with(Item {})
+        .with(Weight { kg: 1.0 })
+        .with(Charges { current: max, max })
+        .with(Ranged { range: 6 })
+        .with(InflictsDamage { damage: 10 })
+        .with(AreaOfEffect {
+            radius: 8,
+            shape: AreaShape::Beam,
+        })
+        .build()
+}
+
+/// Mundo para os testes: todos os componentes, os recursos que os sistemas
+/// pedem, um mapa pequeno e o jogador com `hp` pontos de vida.
+#[cfg(test)]
+pub fn test_world(hp: i32) -> (World, Entity) {
+    use crate::game_log::GameLog;
+    use crate::map::Map;
+    use crate::particle_system::ParticleBuilder;
+
+    let mut world = World::new();
+    crate::register_components(&mut world);
+    world.insert(GameLog::new());
+    world.insert(ItemKnowledge::default());
+    world.insert(ParticleBuilder::default());
+    world.insert(RandomNumberGenerator::seeded(1));
+    world.insert(Map::new(10, 10));
+
+    let player = player(&mut world, 1, 1);
+    world
+        .write_storage::<CombatStats>()
+        .get_mut(player)
+        .unwrap()
+        .hp = hp;
+    world.insert(player);
+    (world, player)
 }
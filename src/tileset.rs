@@ -0,0 +1,174 @@
+use rltk::FontCharType;
+
+use crate::i18n::Text;
+use crate::map::Map;
+use crate::map::TileType;
+
+/// Console dos blocos gráficos, desenhado por baixo de todo o resto.
+pub const TILE_CONSOLE: usize = 0;
+/// Console de texto CP437: a interface, o mapa em ASCII e tudo que não tiver
+/// bloco no tileset.
+pub const ASCII_CONSOLE: usize = 1;
+/// Fonte com os blocos 16x16, dentro de `resources`: o `example_tiles.jpg`
+/// com as peças de parede na segunda linha, na ordem de `wall_mask`.
+pub const TILE_FONT: &str = "tiles.png";
+
+/// Como o mapa e as entidades são desenhados.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RenderMode {
+    Ascii,
+    AsciiLines,
+    Tiles,
+}
+
+impl RenderMode {
+    pub fn next(self) -> RenderMode {
+        match self {
+            RenderMode::Ascii => RenderMode::AsciiLines,
+            RenderMode::AsciiLines => RenderMode::Tiles,
+            RenderMode::Tiles => RenderMode::Ascii,
+        }
+    }
+
+    pub fn name(self) -> Text {
+        Text::message(match self {
+            RenderMode::Ascii => "settings.render.ascii",
+            RenderMode::AsciiLines => "settings.render.ascii_lines",
+            RenderMode::Tiles => "settings.render.tiles",
+        })
+    }
+
+    pub fn tileset(self) -> &'static Tileset {
+        match self {
+            RenderMode::Ascii => &ASCII,
+            RenderMode::AsciiLines => &ASCII_LINES,
+            RenderMode::Tiles => &TILES,
+        }
+    }
+}
+
+/// Que tipo de coisa a entidade é, para escolher o bloco.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EntityKind {
+    Player,
+    Creature,
+    Item,
+    Particle,
+}
+
+/// Glifos de um jeito de desenhar o jogo, todos no mesmo console.
+pub struct Tileset {
+    pub console: usize,
+    pub floor: FontCharType,
+    /// Peça de parede para cada máscara de `wall_mask`.
+    pub walls: [FontCharType; 16],
+    /// Blocos das entidades; quem não estiver aqui sai em ASCII, com o glifo
+    /// do próprio `Renderable`.
+    pub sprites: &'static [(EntityKind, FontCharType)],
+}
+
+impl Tileset {
+    pub fn tile_glyph(&self, map: &Map, x: usize, y: usize) -> FontCharType {
+        match map.tiles[map.xy_idx(x, y)] {
+            TileType::Floor => self.floor,
+            TileType::Wall => self.walls[wall_mask(map, x, y) as usize],
+        }
+    }
+
+    pub fn sprite(&self, kind: EntityKind) -> Option<FontCharType> {
+        self.sprites
+            .iter()
+            .find(|(k, _glyph)| *k == kind)
+            .map(|(_kind, glyph)| *glyph)
+    }
+}
+
+/// CP437 clássico: toda parede é um `#`.
+pub const ASCII: Tileset = Tileset {
+    console: ASCII_CONSOLE,
+    floor: 46,       // .
+    walls: [35; 16], // #
+    sprites: &[],
+};
+
+/// CP437: paredes com traço duplo que se emendam com as vizinhas.
+pub const ASCII_LINES: Tileset = Tileset {
+    console: ASCII_CONSOLE,
+    floor: 46, // .
+    walls: [
+        35,  // # sozinha
+        186, // ║ norte
+        186, // ║ sul
+        186, // ║ norte e sul
+        205, // ═ oeste
+        188, // ╝ norte e oeste
+        187, // ╗ sul e oeste
+        185, // ╣ norte, sul e oeste
+        205, // ═ leste
+        200, // ╚ norte e leste
+        201, // ╔ sul e leste
+        204, // ╠ norte, sul e leste
+        205, // ═ oeste e leste
+        202, // ╩ norte, oeste e leste
+        203, // ╦ sul, oeste e leste
+        206, // ╬ todas
+    ],
+    sprites: &[],
+};
+
+/// Chão, jogador e uma peça de parede por máscara, a partir do bloco 16.
+pub const TILES: Tileset = Tileset {
+    console: TILE_CONSOLE,
+    floor: 0,
+    walls: [
+        16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31,
+    ],
+    sprites: &[(EntityKind::Player, 2)],
+};
+
+/// Quais vizinhas diretas são paredes já vistas: 1 norte, 2 sul, 4 oeste e
+/// 8 leste. Parede que o jogador não viu não conta, para não entregar o mapa.
+pub fn wall_mask(map: &Map, x: usize, y: usize) -> u8 {
+    let neighbours = [(0, -1, 1), (0, 1, 2), (-1, 0, 4), (1, 0, 8)];
+    neighbours
+        .iter()
+        .filter(|(dx, dy, _bit)| {
+            let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+            if !map.in_bounds(nx, ny) {
+                return false;
+            }
+            let idx = map.xy_idx(nx as usize, ny as usize);
+            map.tiles[idx] == TileType::Wall && map.revealed_tiles[idx]
+        })
+        .fold(0, |mask, (_dx, _dy, bit)| mask | bit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walls_join_their_revealed_neighbours() {
+        // ###
+        // #..
+        let mut map = Map::new(3, 2);
+        map.tiles = vec![
+            TileType::Wall,
+            TileType::Wall,
+            TileType::Wall,
+            TileType::Wall,
+            TileType::Floor,
+            TileType::Floor,
+        ];
+        map.revealed_tiles = vec![true; 6];
+        assert_eq!(wall_mask(&map, 0, 0), 2 | 8);
+        assert_eq!(ASCII.tile_glyph(&map, 0, 0), 35);
+        assert_eq!(ASCII_LINES.tile_glyph(&map, 0, 0), 201);
+        assert_eq!(ASCII_LINES.tile_glyph(&map, 1, 0), 205);
+        assert_eq!(TILES.tile_glyph(&map, 0, 0), 16 + (2 | 8));
+        assert_eq!(TILES.tile_glyph(&map, 1, 0), 16 + (4 | 8));
+
+        map.revealed_tiles[3] = false;
+        assert_eq!(wall_mask(&map, 0, 0), 8);
+    }
+}
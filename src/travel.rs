@@ -0,0 +1,255 @@
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+use rltk::BaseMap;
+use rltk::Point;
+use specs::prelude::*;
+
+use crate::components::*;
+use crate::faction::reaction;
+use crate::faction::Reaction;
+use crate::game_log::GameLog;
+use crate::game_log::LogCategory;
+use crate::map::Map;
+use crate::map::TileType;
+use crate::player::player_hp;
+use crate::player::try_move_player;
+use crate::RunState;
+
+/// Viagem automática do jogador: um caminho até um ponto clicado ou a
+/// exploração até a fronteira mais próxima, um passo por turno.
+#[derive(Default)]
+pub struct Travel {
+    path: VecDeque<Point>,
+    exploring: bool,
+    /// Inimigos e itens que já estavam à vista; só os novos interrompem.
+    known: HashSet<Entity>,
+    /// Vida do jogador no passo anterior; se cair, a viagem para.
+    last_hp: i32,
+}
+
+impl Travel {
+    pub fn is_active(&self) -> bool {
+        self.exploring || !self.path.is_empty()
+    }
+
+    pub fn stop(&mut self) {
+        self.path.clear();
+        self.exploring = false;
+        self.known.clear();
+    }
+}
+
+/// Inimigos e itens no chão que o jogador enxerga agora.
+fn sightings(ecs: &World) -> Vec<Entity> {
+    let entities = ecs.entities();
+    let player_entity = ecs.fetch::<Entity>();
+    let viewsheds = ecs.read_storage::<Viewshed>();
+    let positions = ecs.read_storage::<Position>();
+    let factions = ecs.read_storage::<Faction>();
+    let items = ecs.read_storage::<Item>();
+
+    let visible = match viewsheds.get(*player_entity) {
+        Some(viewshed) => &viewshed.visible_tiles,
+        None => return Vec::new(),
+    };
+    (&entities, &positions)
+        .join()
+        .filter(|(entity, _pos)| {
+            items.get(*entity).is_some()
+                || factions
+                    .get(*entity)
+                    .map_or(false, |f| reaction(Faction::Player, *f) == Reaction::Attack)
+        })
+        .filter(|(_entity, pos)| visible.contains(&Point::new(pos.x, pos.y)))
+        .map(|(entity, _pos)| entity)
+        .collect()
+}
+
+fn begin(ecs: &World, path: VecDeque<Point>, exploring: bool) {
+    let known = sightings(ecs).into_iter().collect();
+    let (hp, _max_hp) = player_hp(ecs);
+    let mut travel = ecs.fetch_mut::<Travel>();
+    travel.path = path;
+    travel.exploring = exploring;
+    travel.known = known;
+    travel.last_hp = hp;
+}
+
+/// Começa a andar até a célula clicada, se ela já foi vista e há caminho.
+pub fn travel_to(ecs: &World, target: Point) -> RunState {
+    let path = {
+        let map = ecs.fetch::<Map>();
+        let player_pos = ecs.fetch::<Point>();
+        if !map.in_bounds(target.x, target.y) {
+            return RunState::AwaitingInput;
+        }
+        let end = map.xy_idx(target.x as usize, target.y as usize);
+        if !map.revealed_tiles[end] || map.tiles[end] == TileType::Wall {
+            return RunState::AwaitingInput;
+        }
+        let start = map.xy_idx(player_pos.x as usize, player_pos.y as usize);
+        let path = rltk::a_star_search(start, end, &*map);
+        if !path.success {
+            return RunState::AwaitingInput;
+        }
+        path.steps
+            .iter()
+            .skip(1)
+            .map(|idx| {
+                let (x, y) = map.idx_xy(*idx);
+                Point::new(x, y)
+            })
+            .collect()
+    };
+    begin(ecs, path, false);
+    RunState::AwaitingInput
+}
+
+pub fn explore(ecs: &World) -> RunState {
+    begin(ecs, VecDeque::new(), true);
+    RunState::AwaitingInput
+}
+
+/// Dá o próximo passo da viagem, parando se algo novo aparecer à vista ou
+/// se o jogador se ferir.
+pub fn travel_step(ecs: &mut World) -> RunState {
+    let (hp, _max_hp) = player_hp(ecs);
+    if hp < ecs.fetch::<Travel>().last_hp {
+        ecs.fetch_mut::<Travel>().stop();
+        ecs.fetch_mut::<GameLog>().system("travel.hurt");
+        return RunState::AwaitingInput;
+    }
+    ecs.fetch_mut::<Travel>().last_hp = hp;
+
+    let seen = sightings(ecs);
+    let novelty = {
+        let travel = ecs.fetch::<Travel>();
+        seen.iter().find(|e| !travel.known.contains(e)).copied()
+    };
+    if let Some(novelty) = novelty {
+        ecs.fetch_mut::<Travel>().stop();
+        if let Some(name) = ecs.read_storage::<Name>().get(novelty) {
+            ecs.fetch_mut::<GameLog>()
+                .entry(LogCategory::System, "travel.spotted")
+                .arg("name", &name.name)
+                .log();
+        }
+        return RunState::AwaitingInput;
+    }
+    ecs.fetch_mut::<Travel>().known = seen.into_iter().collect();
+
+    let player_pos = *ecs.fetch::<Point>();
+    let exploring = ecs.fetch::<Travel>().exploring;
+    let next = if exploring {
+        next_explore_step(&ecs.fetch::<Map>(), player_pos)
+    } else {
+        ecs.fetch_mut::<Travel>().path.pop_front()
+    };
+
+    let next = match next {
+        Some(next) => next,
+        None => {
+            ecs.fetch_mut::<Travel>().stop();
+            if exploring {
+                ecs.fetch_mut::<GameLog>().system("travel.nothing_left");
+            }
+            return RunState::AwaitingInput;
+        }
+    };
+
+    try_move_player(next.x - player_pos.x, next.y - player_pos.y, ecs);
+    if *ecs.fetch::<Point>() == player_pos {
+        // algo bloqueou o caminho
+        ecs.fetch_mut::<Travel>().stop();
+        return RunState::AwaitingInput;
+    }
+    RunState::PlayerTurn
+}
+
+/// Primeiro passo do caminho mais curto até uma célula conhecida que faz
+/// fronteira com o desconhecido.
+fn next_explore_step(map: &Map, from: Point) -> Option<Point> {
+    let start = map.xy_idx(from.x as usize, from.y as usize);
+    let mut parents = vec![None; map.tiles.len()];
+    let mut visited = vec![false; map.tiles.len()];
+    let mut queue = VecDeque::new();
+    visited[start] = true;
+    queue.push_back(start);
+
+    while let Some(idx) = queue.pop_front() {
+        if idx != start && is_frontier(map, idx) {
+            let mut step = idx;
+            while let Some(parent) = parents[step] {
+                if parent == start {
+                    break;
+                }
+                step = parent;
+            }
+            let (x, y) = map.idx_xy(step);
+            return Some(Point::new(x, y));
+        }
+        for (exit, _cost) in map.get_available_exits(idx).iter() {
+            if !visited[*exit] && map.revealed_tiles[*exit] {
+                visited[*exit] = true;
+                parents[*exit] = Some(idx);
+                queue.push_back(*exit);
+            }
+        }
+    }
+    None
+}
+
+fn is_frontier(map: &Map, idx: usize) -> bool {
+    let (x, y) = map.idx_xy(idx);
+    (x.saturating_sub(1)..=usize::min(x + 1, map.width - 1)).any(|nx| {
+        (y.saturating_sub(1)..=usize::min(y + 1, map.height - 1))
+            .any(|ny| !map.revealed_tiles[map.xy_idx(nx, ny)])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Um corredor na linha 2, de (1, 2) a (10, 2), todo revelado.
+    fn corridor() -> Map {
+        let mut map = Map::new(12, 5);
+        for x in 1..=10 {
+            let idx = map.xy_idx(x, 2);
+            map.tiles[idx] = TileType::Floor;
+        }
+        map.populate_blocked();
+        map.revealed_tiles = vec![true; map.tiles.len()];
+        map
+    }
+
+    fn hide_column(map: &mut Map, x: usize) {
+        for y in 0..map.height {
+            let idx = map.xy_idx(x, y);
+            map.revealed_tiles[idx] = false;
+        }
+    }
+
+    #[test]
+    fn walks_to_the_nearest_unrevealed_cell() {
+        let mut map = corridor();
+        hide_column(&mut map, 0);
+        hide_column(&mut map, 8);
+
+        let mut at = Point::new(3, 2);
+        for _ in 0..map.width {
+            at = next_explore_step(&map, at).unwrap();
+            if is_frontier(&map, map.xy_idx(at.x as usize, at.y as usize)) {
+                break;
+            }
+        }
+        assert_eq!(at, Point::new(1, 2));
+    }
+
+    #[test]
+    fn nothing_left_to_explore() {
+        let map = corridor();
+        assert_eq!(next_explore_step(&map, Point::new(3, 2)), None);
+    }
+}
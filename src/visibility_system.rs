@@ -1,7 +1,6 @@
 use crate::components::Player;
 use crate::components::Position;
 use crate::components::Viewshed;
-use crate::map::xy_idx;
 use crate::map::Map;
 
 use rltk::field_of_view;
@@ -32,15 +31,13 @@ impl<'a> System<'a> for VisibilitySystem {
                     viewshed.range as i32,
                     &*map,
                 );
-                viewshed.visible_tiles.retain(|p| {
-                    p.x >= 0 && p.x <= map.width as i32 && p.y >= 0 && p.y <= map.height as i32
-                });
+                viewshed.visible_tiles.retain(|p| map.in_bounds(p.x, p.y));
 
                 // If the player, update revealed tiles
-                if let Some(_) = player.get(entity) {
+                if player.get(entity).is_some() {
                     map.visible_tiles.iter_mut().for_each(|t| *t = false);
                     for vis in viewshed.visible_tiles.iter() {
-                        let idx = xy_idx(vis.x, vis.y);
+                        let idx = map.xy_idx(vis.x as usize, vis.y as usize);
                         map.revealed_tiles[idx] = true;
                         map.visible_tiles[idx] = true;
                     }